@@ -9,10 +9,10 @@ anchor_lang::declare_id!("JUP4Fb2cqiRUcaTHdrPC8h2gNsA2ETXiPDD33WcGuJB");
 pub mod jupiter_override {
     use super::Side;
     use anchor_lang::{prelude::*, Discriminator};
-    use anchor_lang::{AnchorSerialize, InstructionData};
+    use anchor_lang::{AnchorDeserialize, AnchorSerialize, InstructionData};
     use std::io::Write;
 
-    #[derive(AnchorSerialize)]
+    #[derive(AnchorSerialize, AnchorDeserialize)]
     pub enum Swap {
         Saber,
         SaberAddDecimalsDeposit,
@@ -44,7 +44,7 @@ pub mod jupiter_override {
         Openbook { side: Side },
     }
 
-    #[derive(AnchorSerialize)]
+    #[derive(AnchorSerialize, AnchorDeserialize)]
     pub struct SplitLeg {
         pub percent: u8,
         pub swap_leg: SwapLeg,
@@ -76,7 +76,28 @@ pub mod jupiter_override {
         }
     }
 
-    #[derive(AnchorSerialize)]
+    impl AnchorDeserialize for SwapLeg {
+        #[inline]
+        fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+            match u8::deserialize(buf)? {
+                0 => Ok(SwapLeg::Chain {
+                    swap_legs: Vec::<SwapLeg>::deserialize(buf)?,
+                }),
+                1 => Ok(SwapLeg::Split {
+                    split_legs: Vec::<SplitLeg>::deserialize(buf)?,
+                }),
+                2 => Ok(SwapLeg::Swap {
+                    swap: Swap::deserialize(buf)?,
+                }),
+                variant => Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("Unexpected SwapLeg variant {}", variant),
+                )),
+            }
+        }
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize)]
     pub struct Route {
         pub swap_leg: SwapLeg,
         pub in_amount: u64,
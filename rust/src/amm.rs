@@ -1,13 +1,33 @@
+use anchor_lang::InstructionData;
 use anyhow::Result;
+use jupiter::jupiter_override::{Route, SwapLeg};
 use rust_decimal::Decimal;
 use serde_json::Value;
-use solana_sdk::{account::Account, pubkey::Pubkey};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
 use std::collections::HashMap;
 
+use crate::error::ErrorCode;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SwapMode {
+    // `amount` is the exact amount of input token to spend
+    #[default]
+    ExactIn,
+    // `amount` is the exact amount of output token to receive
+    ExactOut,
+}
+
 pub struct QuoteParams {
-    pub in_amount: u64,
+    pub amount: u64,
     pub input_mint: Pubkey,
     pub output_mint: Pubkey,
+    pub swap_mode: SwapMode,
+    // Stops the swap at this sqrt price like the program does, `None` walks the whole price range
+    pub sqrt_price_limit: Option<u128>,
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -18,9 +38,17 @@ pub struct Quote {
     pub in_amount: u64,
     pub out_amount: u64,
     pub fee_amount: u64,
+    // Split of fee_amount into liquidity provider, protocol and partner parts, all in fee_mint
+    pub lp_fee_amount: u64,
+    pub protocol_fee_amount: u64,
+    pub partner_fee_amount: u64,
     pub fee_mint: Pubkey,
     pub fee_pct: Decimal,
     pub price_impact_pct: Decimal,
+    // The swap stopped at `sqrt_price_limit` before the amount was used up
+    pub is_price_limited: bool,
+    // The swap needs more tick arrays than the instruction carries, it would fail on chain
+    pub is_tick_arrays_exceeded: bool,
 }
 
 pub type QuoteMintToReferrer = HashMap<Pubkey, Pubkey>;
@@ -36,6 +64,11 @@ pub struct SwapParams {
     pub in_amount: u64,
 }
 
+pub struct SwapLegAndAccountMetas {
+    pub swap_leg: SwapLeg,
+    pub account_metas: Vec<AccountMeta>,
+}
+
 pub trait Amm {
     // Amm name
     fn label(&self) -> String;
@@ -49,6 +82,49 @@ pub trait Amm {
     fn update(&mut self, accounts_map: &HashMap<Pubkey, Vec<u8>>) -> Result<()>;
     // Returns quote for the given quote params
     fn quote(&self, quote_params: &QuoteParams) -> Result<Quote>;
+    // Indicates which Swap has to be performed along with all the necessary account metas
+    fn get_swap_leg_and_account_metas(
+        &self,
+        swap_params: &SwapParams,
+    ) -> Result<SwapLegAndAccountMetas>;
+}
+
+/// Builds the Jupiter `route` instruction of a swap leg, `out_amount` is the quoted out amount
+/// the slippage applies to. The platform fee account, required exactly when `platform_fee_bps`
+/// is not zero, goes after the swap leg accounts.
+pub fn build_route_instruction(
+    swap_leg_and_account_metas: SwapLegAndAccountMetas,
+    swap_params: &SwapParams,
+    out_amount: u64,
+    slippage_bps: u16,
+    platform_fee_bps: u8,
+    platform_fee_account: Option<Pubkey>,
+) -> Result<Instruction> {
+    if (platform_fee_bps > 0) != platform_fee_account.is_some() {
+        return Err(ErrorCode::InvalidPlatformFeeAccount.into());
+    }
+    let mut accounts = vec![
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(swap_params.user_transfer_authority, true),
+        AccountMeta::new_readonly(swap_params.user_destination_token_account, false),
+    ];
+    accounts.extend(swap_leg_and_account_metas.account_metas);
+    if let Some(platform_fee_account) = platform_fee_account {
+        accounts.push(AccountMeta::new(platform_fee_account, false));
+    }
+
+    let route = Route {
+        swap_leg: swap_leg_and_account_metas.swap_leg,
+        in_amount: swap_params.in_amount,
+        quoted_out_amount: out_amount,
+        slippage_bps,
+        platform_fee_bps,
+    };
+    Ok(Instruction {
+        program_id: jupiter::ID,
+        accounts,
+        data: route.data(),
+    })
 }
 
 #[derive(Clone, Debug)]
@@ -0,0 +1,264 @@
+//! Mirror of the current Jupiter amm interface, [crate::amm] follows the older one.
+use anyhow::Result;
+use jupiter::jupiter_override::Swap;
+use rust_decimal::Decimal;
+use serde_json::Value;
+use solana_sdk::{account::Account, instruction::AccountMeta, pubkey::Pubkey};
+use std::collections::HashMap;
+
+use crate::amm::{self, Amm as _, SwapMode};
+use crate::crema::CremaClmm;
+use crate::error::ErrorCode;
+
+pub type AccountMap = HashMap<Pubkey, Account>;
+
+#[derive(Clone, Debug)]
+pub struct KeyedAccount {
+    pub key: Pubkey,
+    pub account: Account,
+    pub params: Option<Value>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct QuoteParams {
+    pub amount: u64,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub swap_mode: SwapMode,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Quote {
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub fee_amount: u64,
+    pub fee_mint: Pubkey,
+    pub fee_pct: Decimal,
+}
+
+#[derive(Debug, Clone)]
+pub struct SwapParams {
+    pub swap_mode: SwapMode,
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub source_mint: Pubkey,
+    pub destination_mint: Pubkey,
+    pub source_token_account: Pubkey,
+    pub destination_token_account: Pubkey,
+    pub token_transfer_authority: Pubkey,
+    pub jupiter_program_id: Pubkey,
+}
+
+pub struct SwapAndAccountMetas {
+    pub swap: Swap,
+    pub account_metas: Vec<AccountMeta>,
+}
+
+pub trait Amm {
+    fn from_keyed_account(keyed_account: &KeyedAccount) -> Result<Self>
+    where
+        Self: Sized;
+    // Amm name
+    fn label(&self) -> String;
+    // The program the amm swaps with
+    fn program_id(&self) -> Pubkey;
+    // identifier, should be your pool address
+    fn key(&self) -> Pubkey;
+    // Token mints that the amm supports for swapping
+    fn get_reserve_mints(&self) -> Vec<Pubkey>;
+    // Accounts related for quoting and creating ix
+    fn get_accounts_to_update(&self) -> Vec<Pubkey>;
+    // Picks data necessary to update it's internal state
+    fn update(&mut self, account_map: &AccountMap) -> Result<()>;
+    // Returns quote for the given quote params
+    fn quote(&self, quote_params: &QuoteParams) -> Result<Quote>;
+    // Indicates which Swap has to be performed along with all the necessary account metas
+    fn get_swap_and_account_metas(&self, swap_params: &SwapParams) -> Result<SwapAndAccountMetas>;
+    // Whether the accounts to update change with the amm state
+    fn has_dynamic_accounts(&self) -> bool {
+        false
+    }
+    fn supports_exact_out(&self) -> bool {
+        false
+    }
+    fn clone_amm(&self) -> Box<dyn Amm + Send + Sync>;
+}
+
+impl Amm for CremaClmm {
+    /// The `params` carry the mint decimals, `{"decimals_a": 6, "decimals_b": 9}`.
+    fn from_keyed_account(keyed_account: &KeyedAccount) -> Result<Self> {
+        let reserve_decimals = reserve_decimals(keyed_account.params.as_ref())?;
+        CremaClmm::new(&keyed_account.key, &keyed_account.account, reserve_decimals)
+    }
+
+    fn label(&self) -> String {
+        amm::Amm::label(self)
+    }
+
+    fn program_id(&self) -> Pubkey {
+        CremaClmm::program_id(self)
+    }
+
+    fn key(&self) -> Pubkey {
+        amm::Amm::key(self)
+    }
+
+    fn get_reserve_mints(&self) -> Vec<Pubkey> {
+        self.get_reserve_mint()
+    }
+
+    fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+        amm::Amm::get_accounts_to_update(self)
+    }
+
+    /// The tick arrays of a changed tick array map are only listed after this update, the ones
+    /// missing from `account_map` are picked on the next update and quoting fails until then.
+    fn update(&mut self, account_map: &AccountMap) -> Result<()> {
+        self.update_accounts(
+            |address| {
+                account_map
+                    .get(address)
+                    .map(|account| account.data.as_slice())
+            },
+            false,
+        )
+    }
+
+    fn quote(&self, quote_params: &QuoteParams) -> Result<Quote> {
+        if self.missing_tick_arrays() > 0 {
+            return Err(ErrorCode::TickArraysNotLoaded(self.missing_tick_arrays()).into());
+        }
+        let quote = amm::Amm::quote(
+            self,
+            &amm::QuoteParams {
+                amount: quote_params.amount,
+                input_mint: quote_params.input_mint,
+                output_mint: quote_params.output_mint,
+                swap_mode: quote_params.swap_mode,
+                sqrt_price_limit: None,
+            },
+        )?;
+        Ok(Quote {
+            in_amount: quote.in_amount,
+            out_amount: quote.out_amount,
+            fee_amount: quote.fee_amount,
+            fee_mint: quote.fee_mint,
+            fee_pct: quote.fee_pct,
+        })
+    }
+
+    fn get_swap_and_account_metas(&self, swap_params: &SwapParams) -> Result<SwapAndAccountMetas> {
+        let swap_leg_and_account_metas = self.get_swap_leg_and_account_metas(&amm::SwapParams {
+            source_mint: swap_params.source_mint,
+            destination_mint: swap_params.destination_mint,
+            user_source_token_account: swap_params.source_token_account,
+            user_destination_token_account: swap_params.destination_token_account,
+            user_transfer_authority: swap_params.token_transfer_authority,
+            open_order_address: None,
+            quote_mint_to_referrer: None,
+            in_amount: swap_params.in_amount,
+        })?;
+        Ok(SwapAndAccountMetas {
+            swap: Swap::Crema,
+            account_metas: swap_leg_and_account_metas.account_metas,
+        })
+    }
+
+    fn has_dynamic_accounts(&self) -> bool {
+        true
+    }
+
+    fn supports_exact_out(&self) -> bool {
+        true
+    }
+
+    fn clone_amm(&self) -> Box<dyn Amm + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+/// Reads the decimals of token a and token b from the keyed account params.
+fn reserve_decimals(params: Option<&Value>) -> Result<[u8; 2], ErrorCode> {
+    let decimals = |name: &str| {
+        params
+            .and_then(|params| params.get(name))
+            .and_then(Value::as_u64)
+            .and_then(|decimals| u8::try_from(decimals).ok())
+            .ok_or_else(|| ErrorCode::InvalidAmmParams(format!("{} is missing", name)))
+    };
+    Ok([decimals("decimals_a")?, decimals("decimals_b")?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::swap_with_partner::SWAP_PROGRAM_ID;
+    use crate::state::test_utils::{clmmpool_account_data, zeroed_account_data};
+    use crate::state::{
+        clmm_config::ClmmConfig, tick_array::TickArray, tick_array_map::TickArrayMap,
+    };
+    use serde_json::json;
+
+    fn account(data: Vec<u8>) -> Account {
+        Account {
+            data,
+            owner: SWAP_PROGRAM_ID,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_crema_amm() {
+        let key = Pubkey::new_unique();
+        let pool = account(clmmpool_account_data(60));
+        let mut keyed_account = KeyedAccount {
+            key,
+            account: pool.clone(),
+            params: None,
+        };
+        assert!(<CremaClmm as Amm>::from_keyed_account(&keyed_account).is_err());
+        keyed_account.params = Some(json!({"decimals_a": 6, "decimals_b": 9}));
+        let amm = <CremaClmm as Amm>::from_keyed_account(&keyed_account).unwrap();
+        let mut amm = amm.clone_amm();
+        assert!(amm.has_dynamic_accounts() && amm.supports_exact_out());
+        assert_eq!(amm.program_id(), SWAP_PROGRAM_ID);
+
+        let mut tick_array_map = account(zeroed_account_data::<TickArrayMap>());
+        // array 115 covers tick 0 with tick spacing 60
+        tick_array_map.data[8 + 115 / 8] |= 1 << (115 % 8);
+        let mut account_map = AccountMap::new();
+        account_map.insert(key, pool);
+        account_map.insert(
+            TickArrayMap::find_address(&key, &SWAP_PROGRAM_ID),
+            tick_array_map,
+        );
+        account_map.insert(
+            Pubkey::default(),
+            account(zeroed_account_data::<ClmmConfig>()),
+        );
+
+        // the tick array is listed once the tick array map is known
+        let accounts = amm.get_accounts_to_update();
+        assert_eq!(accounts.len(), 3);
+        amm.update(&account_map).unwrap();
+        let accounts = amm.get_accounts_to_update();
+        assert_eq!(accounts.len(), 4);
+
+        // quoting waits for the listed tick array
+        let quote_params = QuoteParams {
+            amount: 100,
+            input_mint: Pubkey::default(),
+            output_mint: Pubkey::default(),
+            swap_mode: SwapMode::ExactIn,
+        };
+        let err = amm.quote(&quote_params).err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<ErrorCode>(),
+            Some(ErrorCode::TickArraysNotLoaded(1))
+        ));
+        account_map.insert(accounts[3], account(zeroed_account_data::<TickArray>()));
+        let mut crema = <CremaClmm as Amm>::from_keyed_account(&keyed_account).unwrap();
+        Amm::update(&mut crema, &account_map).unwrap();
+        assert_eq!(crema.missing_tick_arrays(), 0);
+    }
+}
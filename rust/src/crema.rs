@@ -1,17 +1,38 @@
-use borsh::BorshDeserialize;
+use jupiter::jupiter_override::{Swap, SwapLeg};
 use lazy_static::lazy_static;
 use rust_decimal::Decimal;
-use solana_sdk::{pubkey, pubkey::Pubkey};
+use solana_sdk::{
+    account::{from_account, Account},
+    clock::Clock,
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    sysvar,
+};
 use std::collections::HashMap;
 use std::ops::{Div, Mul, Sub};
 
+use crate::error::ErrorCode;
+use crate::instructions::{
+    partner::partner_fee_vault,
+    swap::{swap, swap_tick_array_accounts},
+    swap_with_partner::swap_with_partner,
+};
 use crate::pair::fetcher::{TickArrayInfo, TickInfo};
+use crate::state::clmm_config::ClmmConfig;
 use crate::state::clmmpool::Clmmpool;
-use crate::state::tick_array_map::TickArrayMap;
+use crate::state::partner::Partner;
+use crate::state::tick_array_map::{TickArrayMap, MAX_SWAP_TICK_ARRAYS};
+use crate::state::AnchorAccount;
 use crate::{
-    amm::{Amm, KeyedAccount, Quote, QuoteParams},
+    amm::{Amm, KeyedAccount, Quote, QuoteParams, SwapLegAndAccountMetas, SwapMode, SwapParams},
     math::sqrt_price::SqrtPrice,
-    pair::{fetcher::PoolInfo, simulate_swap::compute_swap},
+    pair::{
+        arbitrage::{compute_arbitrage, ArbitrageResult},
+        depth::{market_depth, MarketDepth},
+        fetcher::PoolInfo,
+        simulate_swap::{compute_swap_in_tick_arrays, default_sqrt_price_limit, ComputeSwapResult},
+    },
     state::tick_array::TickArray,
 };
 use anyhow::Result;
@@ -29,6 +50,14 @@ lazy_static! {
     };
 }
 
+/// A quote together with the tick arrays the swap instruction has to carry.
+#[derive(Debug, Clone)]
+pub struct TickArraysQuote {
+    pub quote: Quote,
+    pub tick_arrays: Vec<Pubkey>,
+}
+
+#[derive(Clone)]
 pub struct CremaClmm {
     key: Pubkey,
     label: String,
@@ -39,112 +68,104 @@ pub struct CremaClmm {
     fee_rate: u16,
     pool_info: PoolInfo,
     tick_array_map_addr: Pubkey,
+    partner: Option<Pubkey>,
+    /// The listed tick arrays skipped by the last update.
+    missing_tick_arrays: usize,
 }
 
 impl CremaClmm {
     pub fn from_keyed_account(keyed_account: &KeyedAccount) -> Result<Self> {
-        let clmmpool = Clmmpool::try_from_slice(&keyed_account.account.data[8..]).unwrap();
-        let reserve_mints = [clmmpool.token_a.clone(), clmmpool.token_b.clone()];
+        let mut amm = Self::new(
+            &keyed_account.key,
+            &keyed_account.account,
+            [keyed_account.decimals_a, keyed_account.decimals_b],
+        )?;
+        amm.pool_info.tick_array_map =
+            TickArrayMap::try_deserialize(&amm.tick_array_map_addr, &keyed_account.tick_array_map)?;
+        Ok(amm)
+    }
+
+    /// Builds the amm from the clmmpool account, the tick array map is loaded on update.
+    pub(crate) fn new(key: &Pubkey, account: &Account, reserve_decimals: [u8; 2]) -> Result<Self> {
+        let clmmpool = Clmmpool::try_deserialize(key, &account.data)?;
+        let reserve_mints = [clmmpool.token_a, clmmpool.token_b];
         let label = CREMA_PROGRAMS
-            .get(&keyed_account.account.owner)
-            .unwrap()
+            .get(&account.owner)
+            .ok_or(ErrorCode::UnknownProgramOwner(account.owner))?
             .clone();
 
-        let mut pool_info = PoolInfo::default();
-        pool_info.pool = clmmpool;
-
-        let tick_array_map_addr =
-            TickArrayMap::find_address(&keyed_account.key, &keyed_account.account.owner);
-        let tick_array_map =
-            TickArrayMap::try_from_slice(&(*keyed_account.tick_array_map)[8..]).unwrap();
-        pool_info.tick_array_map = tick_array_map;
+        let pool_info = PoolInfo {
+            pool: clmmpool,
+            ..Default::default()
+        };
 
         Ok(Self {
-            key: keyed_account.key,
+            key: *key,
             label,
             reserve_mints,
-            reserve_decimals: [keyed_account.decimals_a, keyed_account.decimals_b],
-            program_id: keyed_account.account.owner,
-            pool_address: keyed_account.key,
+            reserve_decimals,
+            program_id: account.owner,
+            pool_address: *key,
             pool_info,
             fee_rate: 0,
-            tick_array_map_addr,
+            tick_array_map_addr: TickArrayMap::find_address(key, &account.owner),
+            partner: None,
+            missing_tick_arrays: 0,
         })
     }
 
-    #[allow(dead_code)]
-    fn get_authority(&self) -> Pubkey {
-        Pubkey::find_program_address(&[&self.key.to_bytes()], &self.program_id).0
+    pub(crate) fn program_id(&self) -> Pubkey {
+        self.program_id
     }
 
-    #[allow(dead_code)]
-    fn clone(&self) -> CremaClmm {
-        CremaClmm {
-            key: self.key,
-            label: self.label.clone(),
-            reserve_mints: self.reserve_mints,
-            reserve_decimals: self.reserve_decimals,
-            fee_rate: self.fee_rate,
-            pool_address: self.pool_address,
-            pool_info: PoolInfo {
-                pool: self.pool_info.pool.clone(),
-                tick_array_map: self.pool_info.tick_array_map.clone(),
-                tick_arrays: self.pool_info.tick_arrays.clone(),
-                ticks: self.pool_info.ticks.clone(),
-            },
-            tick_array_map_addr: self.tick_array_map_addr,
-            program_id: self.program_id,
-        }
+    /// The tick arrays listed by the tick array map that the last update skipped.
+    pub(crate) fn missing_tick_arrays(&self) -> usize {
+        self.missing_tick_arrays
     }
-}
 
-impl Amm for CremaClmm {
-    fn label(&self) -> String {
-        self.label.clone()
-    }
-
-    fn key(&self) -> Pubkey {
-        self.key
-    }
-
-    fn get_reserve_mint(&self) -> Vec<Pubkey> {
-        self.reserve_mints.to_vec()
-    }
-
-    fn get_accounts_to_update(&self) -> Vec<Pubkey> {
-        let mut accounts = vec![self.pool_address];
-        accounts.push(self.tick_array_map_addr);
-
-        let mut tick_arrays = vec![];
-        for array_index in 0..868 * 8 {
-            if !self.pool_info.tick_array_map.is_seted(array_index) {
-                continue;
-            }
-            let address =
-                TickArray::find_address(&self.pool_address, array_index as u16, &self.program_id);
-            tick_arrays.push(address);
-        }
-
-        println!("tick arrays: {:?}", tick_arrays);
-
-        accounts.extend(tick_arrays.iter());
-        accounts
-    }
-
-    fn update(&mut self, accounts_map: &HashMap<Pubkey, Vec<u8>>) -> Result<()> {
-        let clmmpool_data = accounts_map.get(&self.pool_address).unwrap();
-        self.pool_info.pool = Clmmpool::try_from_slice(&clmmpool_data[8..]).unwrap();
+    /// Picks the pool state from the accounts `get_data` returns. Tick arrays that are not
+    /// available yet are skipped and counted unless `require_tick_arrays`.
+    pub(crate) fn update_accounts<'a>(
+        &mut self,
+        get_data: impl Fn(&Pubkey) -> Option<&'a [u8]>,
+        require_tick_arrays: bool,
+    ) -> Result<()> {
+        let get_account_data =
+            |address: &Pubkey| get_data(address).ok_or(ErrorCode::AccountNotFound(*address));
+
+        let clmmpool_data = get_account_data(&self.pool_address)?;
+        self.pool_info.pool = Clmmpool::try_deserialize(&self.pool_address, clmmpool_data)?;
         self.fee_rate = self.pool_info.pool.fee_rate;
         self.reserve_mints = [self.pool_info.pool.token_a, self.pool_info.pool.token_b];
 
-        let tick_array_map_data = accounts_map.get(&self.tick_array_map_addr).unwrap();
-
+        let tick_array_map_data = get_account_data(&self.tick_array_map_addr)?;
         self.pool_info.tick_array_map =
-            TickArrayMap::try_from_slice(&tick_array_map_data[8..]).unwrap();
+            TickArrayMap::try_deserialize(&self.tick_array_map_addr, tick_array_map_data)?;
+
+        let clmm_config_addr = self.pool_info.pool.clmm_config;
+        let clmm_config_data = get_account_data(&clmm_config_addr)?;
+        let clmm_config = ClmmConfig::try_deserialize(&clmm_config_addr, clmm_config_data)?;
+        self.pool_info.protocol_fee_rate = clmm_config.protocol_fee_rate;
+
+        if let Some(partner_addr) = self.partner {
+            let partner_data = get_account_data(&partner_addr)?;
+            let partner = Partner::try_deserialize(&partner_addr, partner_data)?;
+            // The partner period is checked against the chain clock like the program does.
+            let clock_data = get_account_data(&sysvar::clock::id())?;
+            let clock: Clock = from_account(&Account {
+                data: clock_data.to_vec(),
+                ..Default::default()
+            })
+            .ok_or(ErrorCode::AccountDeserializeFailed(sysvar::clock::id()))?;
+            let now = u64::try_from(clock.unix_timestamp)
+                .map_err(|_| ErrorCode::AccountDeserializeFailed(sysvar::clock::id()))?;
+            self.pool_info.partner_fee_rate = partner.current_fee_rate(now);
+        }
 
         // fetch tick array
         let mut ticks = vec![];
         let mut tick_arrays = vec![];
+        let mut missing_tick_arrays = 0;
 
         for array_index in 0..868 * 8 {
             if !self.pool_info.tick_array_map.is_seted(array_index) {
@@ -152,8 +173,15 @@ impl Amm for CremaClmm {
             }
             let address =
                 TickArray::find_address(&self.pool_address, array_index as u16, &self.program_id);
-            let tick_array_data = accounts_map.get(&address).unwrap();
-            let tick_array: TickArray = TickArray::try_from_slice(&tick_array_data[8..]).unwrap();
+            let tick_array_data = match get_data(&address) {
+                Some(data) => data,
+                None if !require_tick_arrays => {
+                    missing_tick_arrays += 1;
+                    continue;
+                }
+                None => return Err(ErrorCode::AccountNotFound(address).into()),
+            };
+            let tick_array = TickArray::try_deserialize(&address, tick_array_data)?;
             tick_arrays.push(TickArrayInfo::from(address, &tick_array));
             for tick in tick_array.ticks {
                 if tick.is_initialized {
@@ -164,23 +192,157 @@ impl Amm for CremaClmm {
 
         self.pool_info.tick_arrays = tick_arrays;
         self.pool_info.ticks = ticks;
+        self.missing_tick_arrays = missing_tick_arrays;
         Ok(())
     }
 
-    fn quote(&self, quote_params: &QuoteParams) -> Result<Quote> {
-        let (a2b, fee_mint) = if self.pool_info.pool.token_a.eq(&quote_params.input_mint) {
-            (true, self.pool_info.pool.token_b)
-        } else {
-            (false, self.pool_info.pool.token_a)
-        };
-        let by_amount_in = true;
+    /// Sets the partner swaps go through, its fee rate is picked up on the next update.
+    pub fn set_partner(&mut self, partner: Option<Pubkey>) {
+        self.partner = partner;
+        if partner.is_none() {
+            self.pool_info.partner_fee_rate = 0;
+        }
+    }
+
+    fn is_a2b(&self, quote_params: &QuoteParams) -> bool {
+        self.pool_info.pool.token_a.eq(&quote_params.input_mint)
+    }
+
+    fn sqrt_price_limit(&self, quote_params: &QuoteParams, a2b: bool) -> u128 {
+        quote_params
+            .sqrt_price_limit
+            .unwrap_or_else(|| default_sqrt_price_limit(a2b))
+    }
 
-        let swap_result = compute_swap(
+    /// Quotes a swap limited to the first `tick_array_count` tick arrays the swap instruction
+    /// would carry, see [MAX_SWAP_TICK_ARRAYS].
+    pub fn quote_in_tick_arrays(
+        &self,
+        quote_params: &QuoteParams,
+        tick_array_count: usize,
+    ) -> Result<TickArraysQuote> {
+        let a2b = self.is_a2b(quote_params);
+        let by_amount_in = quote_params.swap_mode == SwapMode::ExactIn;
+
+        let (swap_result, array_indexes) = compute_swap_in_tick_arrays(
             self.pool_info.clone(),
             a2b,
             by_amount_in,
-            quote_params.in_amount,
-        );
+            quote_params.amount,
+            self.sqrt_price_limit(quote_params, a2b),
+            tick_array_count,
+        )?;
+        let tick_arrays = array_indexes
+            .iter()
+            .map(|array_index| {
+                TickArray::find_address(&self.pool_address, *array_index, &self.program_id)
+            })
+            .collect();
+
+        Ok(TickArraysQuote {
+            quote: self.build_quote(quote_params, a2b, &swap_result),
+            tick_arrays,
+        })
+    }
+
+    /// The amounts to sell to move the pool price down and up by each of `price_changes`, 0.01
+    /// for 1%, against the pool state of the last update.
+    pub fn market_depth(&self, price_changes: &[Decimal]) -> Result<Vec<MarketDepth>> {
+        let mut depths = Vec::with_capacity(price_changes.len());
+        for price_change in price_changes {
+            depths.push(market_depth(&self.pool_info, *price_change)?);
+        }
+        Ok(depths)
+    }
+
+    /// Sizes the swap that moves the pool to `price`, the ui price of token a in token b, against
+    /// the pool state of the last update.
+    pub fn compute_arbitrage(&self, price: Decimal) -> Result<ArbitrageResult> {
+        Ok(compute_arbitrage(
+            &self.pool_info,
+            price,
+            self.reserve_decimals[0],
+            self.reserve_decimals[1],
+        )?)
+    }
+
+    /// Builds the `swap` instruction against the pool state of the last update.
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap_instruction(
+        &self,
+        account_a: &Pubkey,
+        account_b: &Pubkey,
+        a_to_b: bool,
+        amount: u64,
+        amount_limit: u64,
+        sqrt_price_limit: u128,
+        by_amount_in: bool,
+        owner: Pubkey,
+    ) -> Instruction {
+        swap(
+            &self.program_id,
+            &self.pool_address,
+            &self.pool_info.pool,
+            &self.pool_info.tick_array_map,
+            account_a,
+            account_b,
+            a_to_b,
+            amount,
+            amount_limit,
+            sqrt_price_limit,
+            by_amount_in,
+            owner,
+        )
+    }
+
+    /// Builds the `swap_with_partner` instruction against the pool state of the last update.
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap_with_partner_instruction(
+        &self,
+        account_a: &Pubkey,
+        account_b: &Pubkey,
+        partner: &Pubkey,
+        partner_ata_a: &Pubkey,
+        partner_ata_b: &Pubkey,
+        a_to_b: bool,
+        amount: u64,
+        amount_limit: u64,
+        sqrt_price_limit: u128,
+        by_amount_in: bool,
+        payer: Pubkey,
+    ) -> Instruction {
+        swap_with_partner(
+            &self.program_id,
+            &self.pool_address,
+            &self.pool_info.pool,
+            &self.pool_info.tick_array_map,
+            account_a,
+            account_b,
+            partner,
+            partner_ata_a,
+            partner_ata_b,
+            a_to_b,
+            amount,
+            amount_limit,
+            sqrt_price_limit,
+            by_amount_in,
+            payer,
+        )
+    }
+
+    fn build_quote(
+        &self,
+        quote_params: &QuoteParams,
+        a2b: bool,
+        swap_result: &ComputeSwapResult,
+    ) -> Quote {
+        // The fee is charged in the input token.
+        let fee_mint = if a2b {
+            self.pool_info.pool.token_a
+        } else {
+            self.pool_info.pool.token_b
+        };
+        let by_amount_in = quote_params.swap_mode == SwapMode::ExactIn;
 
         let before_sqrt_price = SqrtPrice::new(self.pool_info.pool.current_sqrt_price);
         let before_price = if a2b {
@@ -197,9 +359,9 @@ impl Amm for CremaClmm {
         };
 
         let not_enough_liquidity = if by_amount_in {
-            swap_result.amount_in < quote_params.in_amount
+            swap_result.amount_in < quote_params.amount
         } else {
-            swap_result.amount_out < quote_params.in_amount
+            swap_result.amount_out < quote_params.amount
         };
 
         let fee_pct = Decimal::from_f32_retain(self.fee_rate as f32).unwrap();
@@ -213,16 +375,129 @@ impl Amm for CremaClmm {
             "{:?}::{:?}::{:?}",
             before_price, after_price, price_impact_pct
         );
-        Ok(Quote {
+        Quote {
             not_enough_liquidity,
             min_in_amount: Option::None,
             min_out_amount: Option::None,
             in_amount: swap_result.amount_in as u64,
             out_amount: swap_result.amount_out as u64,
-            fee_amount: quote_params.in_amount * (self.fee_rate as u64) / 1000000,
+            fee_amount: swap_result.fee_amount,
+            lp_fee_amount: swap_result.lp_fee_amount,
+            protocol_fee_amount: swap_result.protocol_fee_amount,
+            partner_fee_amount: swap_result.partner_fee_amount,
             fee_mint,
             fee_pct,
             price_impact_pct,
+            is_price_limited: swap_result.is_price_limited,
+            is_tick_arrays_exceeded: swap_result.is_tick_arrays_exceeded,
+        }
+    }
+
+    #[allow(dead_code)]
+    fn get_authority(&self) -> Pubkey {
+        Pubkey::find_program_address(&[&self.key.to_bytes()], &self.program_id).0
+    }
+}
+
+impl Amm for CremaClmm {
+    fn label(&self) -> String {
+        self.label.clone()
+    }
+
+    fn key(&self) -> Pubkey {
+        self.key
+    }
+
+    fn get_reserve_mint(&self) -> Vec<Pubkey> {
+        self.reserve_mints.to_vec()
+    }
+
+    fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+        let mut accounts = vec![self.pool_address];
+        accounts.push(self.tick_array_map_addr);
+        accounts.push(self.pool_info.pool.clmm_config);
+        if let Some(partner) = self.partner {
+            accounts.push(partner);
+            accounts.push(sysvar::clock::id());
+        }
+
+        let mut tick_arrays = vec![];
+        for array_index in 0..868 * 8 {
+            if !self.pool_info.tick_array_map.is_seted(array_index) {
+                continue;
+            }
+            let address =
+                TickArray::find_address(&self.pool_address, array_index as u16, &self.program_id);
+            tick_arrays.push(address);
+        }
+
+        println!("tick arrays: {:?}", tick_arrays);
+
+        accounts.extend(tick_arrays.iter());
+        accounts
+    }
+
+    fn update(&mut self, accounts_map: &HashMap<Pubkey, Vec<u8>>) -> Result<()> {
+        self.update_accounts(
+            |address| accounts_map.get(address).map(|data| data.as_slice()),
+            true,
+        )
+    }
+
+    /// Quotes the swap over the tick arrays the swap instruction carries.
+    fn quote(&self, quote_params: &QuoteParams) -> Result<Quote> {
+        Ok(self
+            .quote_in_tick_arrays(quote_params, MAX_SWAP_TICK_ARRAYS)?
+            .quote)
+    }
+
+    fn get_swap_leg_and_account_metas(
+        &self,
+        swap_params: &SwapParams,
+    ) -> Result<SwapLegAndAccountMetas> {
+        let partner = self.partner.ok_or(ErrorCode::PartnerNotSet)?;
+        let pool = &self.pool_info.pool;
+        let a2b = swap_params.source_mint == pool.token_a;
+        let (account_a, account_b) = if a2b {
+            (
+                swap_params.user_source_token_account,
+                swap_params.user_destination_token_account,
+            )
+        } else {
+            (
+                swap_params.user_destination_token_account,
+                swap_params.user_source_token_account,
+            )
+        };
+
+        let mut account_metas = vec![
+            AccountMeta::new_readonly(self.program_id, false),
+            AccountMeta::new_readonly(pool.clmm_config, false),
+            AccountMeta::new(self.pool_address, false),
+            AccountMeta::new_readonly(pool.token_a, false),
+            AccountMeta::new_readonly(pool.token_b, false),
+            AccountMeta::new(account_a, false),
+            AccountMeta::new(account_b, false),
+            AccountMeta::new(pool.token_a_vault, false),
+            AccountMeta::new(pool.token_b_vault, false),
+            AccountMeta::new(self.tick_array_map_addr, false),
+            AccountMeta::new_readonly(swap_params.user_transfer_authority, true),
+            AccountMeta::new_readonly(partner, false),
+            AccountMeta::new(partner_fee_vault(&partner, &pool.token_a), false),
+            AccountMeta::new(partner_fee_vault(&partner, &pool.token_b), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ];
+        account_metas.extend(swap_tick_array_accounts(
+            &self.program_id,
+            &self.pool_address,
+            pool,
+            &self.pool_info.tick_array_map,
+            a2b,
+        ));
+
+        Ok(SwapLegAndAccountMetas {
+            swap_leg: SwapLeg::Swap { swap: Swap::Crema },
+            account_metas,
         })
     }
 }
@@ -232,16 +507,256 @@ mod tests {
     use std::time::Duration;
 
     use crate::{
-        amm::{Amm, QuoteParams},
+        amm::{build_route_instruction, Amm, KeyedAccount, QuoteParams, SwapMode, SwapParams},
         crema::CremaClmm,
+        error::ErrorCode,
         harness::Harness,
         instructions::swap_with_partner::SWAP_PROGRAM_ID,
-        state::clmmpool::Clmmpool,
+        math::tick_math::get_sqrt_price_at_tick,
+        pair::{fetcher::PoolInfo, test_utils::pool_info},
+        state::test_utils::{clmmpool_account_data, zeroed_account_data},
+        state::{
+            clmm_config::ClmmConfig, clmmpool::Clmmpool, partner::Partner,
+            tick_array_map::TickArrayMap, DISCRIMINATOR_LEN,
+        },
     };
     use solana_cli_config::Config;
     use solana_client::rpc_client::RpcClient;
+    use solana_sdk::account::{create_account_for_test, Account};
+    use solana_sdk::clock::Clock;
     use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+    use solana_sdk::sysvar;
     use solana_sdk::{pubkey, pubkey::Pubkey};
+    use std::collections::HashMap;
+
+    fn keyed_account(data: Vec<u8>, owner: Pubkey) -> KeyedAccount {
+        KeyedAccount {
+            key: Pubkey::new_unique(),
+            account: Account {
+                data,
+                owner,
+                ..Default::default()
+            },
+            params: None,
+            decimals_a: 6,
+            decimals_b: 6,
+            tick_array_map: Box::default(),
+        }
+    }
+
+    #[test]
+    fn test_from_keyed_account_errors() {
+        let err = CremaClmm::from_keyed_account(&keyed_account(vec![0; 16], SWAP_PROGRAM_ID))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<ErrorCode>(),
+            Some(ErrorCode::InvalidAccountSize { .. })
+        ));
+
+        let owner = Pubkey::new_unique();
+        let data = zeroed_account_data::<Clmmpool>();
+        let err = CremaClmm::from_keyed_account(&keyed_account(data.clone(), owner))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<ErrorCode>(),
+            Some(ErrorCode::UnknownProgramOwner(o)) if *o == owner
+        ));
+
+        // the tick array map is missing
+        let err = CremaClmm::from_keyed_account(&keyed_account(data, SWAP_PROGRAM_ID))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<ErrorCode>(),
+            Some(ErrorCode::InvalidAccountSize { .. })
+        ));
+    }
+
+    #[test]
+    fn test_quote_with_price_limit() {
+        let keyed_account = keyed_account(clmmpool_account_data(60), SWAP_PROGRAM_ID);
+        let mut amm = CremaClmm::new(&keyed_account.key, &keyed_account.account, [6, 6]).unwrap();
+        // The a2b swap crosses tick arrays 115, 113, 112 and 110.
+        amm.pool_info = pool_info(&[(-20000, 20000), (-12000, 12000), (-6000, 6000), (-600, 600)]);
+
+        let limit = get_sqrt_price_at_tick(-100);
+        let mut quote_params = QuoteParams {
+            amount: 1_000_000_000_000,
+            input_mint: Pubkey::default(),
+            output_mint: Pubkey::new_unique(),
+            swap_mode: SwapMode::ExactIn,
+            sqrt_price_limit: Some(limit),
+        };
+        let quote = amm.quote(&quote_params).unwrap();
+        assert!(quote.is_price_limited && quote.not_enough_liquidity);
+        assert!(quote.in_amount < quote_params.amount);
+
+        // Without limit the swap runs past the three tick arrays the instruction carries.
+        quote_params.sqrt_price_limit = None;
+        quote_params.amount = u64::MAX / 2;
+        assert!(amm.quote(&quote_params).unwrap().is_tick_arrays_exceeded);
+
+        quote_params.sqrt_price_limit = Some(get_sqrt_price_at_tick(100));
+        let err = amm.quote(&quote_params).err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<ErrorCode>(),
+            Some(ErrorCode::WrongSqrtPriceLimit)
+        ));
+    }
+
+    #[test]
+    fn test_quote_exact_out() {
+        let keyed_account = keyed_account(clmmpool_account_data(60), SWAP_PROGRAM_ID);
+        let mut amm = CremaClmm::new(&keyed_account.key, &keyed_account.account, [6, 6]).unwrap();
+        amm.pool_info = pool_info(&[(-6000, 6000), (-600, 600)]);
+
+        let mut quote_params = QuoteParams {
+            amount: 1_000_000,
+            input_mint: Pubkey::default(),
+            output_mint: Pubkey::new_unique(),
+            swap_mode: SwapMode::ExactOut,
+            sqrt_price_limit: None,
+        };
+        let quote = amm.quote(&quote_params).unwrap();
+        assert!(!quote.not_enough_liquidity);
+        assert_eq!(quote.out_amount, quote_params.amount);
+        assert!(quote.in_amount > 0);
+
+        // More than the pool holds in the output token.
+        quote_params.amount = u64::MAX / 2;
+        let quote = amm.quote(&quote_params).unwrap();
+        assert!(quote.not_enough_liquidity);
+        assert!(quote.out_amount < quote_params.amount);
+    }
+
+    #[test]
+    fn test_partner_fee_follows_chain_clock() {
+        let mut keyed_account = keyed_account(clmmpool_account_data(60), SWAP_PROGRAM_ID);
+        *keyed_account.tick_array_map = zeroed_account_data::<TickArrayMap>();
+        let mut amm = CremaClmm::from_keyed_account(&keyed_account).unwrap();
+        let partner = Pubkey::new_unique();
+        amm.set_partner(Some(partner));
+        assert!(amm.get_accounts_to_update().contains(&sysvar::clock::id()));
+
+        // The partner takes 20% of the protocol fee from 100 until 200.
+        let mut partner_data = zeroed_account_data::<Partner>();
+        let offset = DISCRIMINATOR_LEN + 3 * 32;
+        partner_data[offset..offset + 2].copy_from_slice(&2000u16.to_le_bytes());
+        partner_data[offset + 3..offset + 11].copy_from_slice(&100u64.to_le_bytes());
+        partner_data[offset + 11..offset + 19].copy_from_slice(&200u64.to_le_bytes());
+        let mut accounts_map = HashMap::from([
+            (keyed_account.key, keyed_account.account.data.clone()),
+            (
+                amm.tick_array_map_addr,
+                zeroed_account_data::<TickArrayMap>(),
+            ),
+            (
+                amm.pool_info.pool.clmm_config,
+                zeroed_account_data::<ClmmConfig>(),
+            ),
+            (partner, partner_data),
+        ]);
+        let quote_params = QuoteParams {
+            amount: 1_000_000,
+            input_mint: Pubkey::default(),
+            output_mint: Pubkey::new_unique(),
+            swap_mode: SwapMode::ExactIn,
+            sqrt_price_limit: None,
+        };
+
+        let mut partner_fee_at = |unix_timestamp| {
+            let clock = Clock {
+                unix_timestamp,
+                ..Default::default()
+            };
+            accounts_map.insert(sysvar::clock::id(), create_account_for_test(&clock).data);
+            Amm::update(&mut amm, &accounts_map).unwrap();
+            amm.pool_info = PoolInfo {
+                protocol_fee_rate: 2000,
+                partner_fee_rate: amm.pool_info.partner_fee_rate,
+                ..pool_info(&[(-6000, 6000)])
+            };
+            amm.quote(&quote_params).unwrap().partner_fee_amount
+        };
+        assert_eq!(partner_fee_at(99), 0);
+        assert!(partner_fee_at(100) > 0);
+        assert_eq!(partner_fee_at(200), 0);
+
+        // The partner fee rate is unknown without the clock.
+        accounts_map.remove(&sysvar::clock::id());
+        let err = Amm::update(&mut amm, &accounts_map).err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<ErrorCode>(),
+            Some(ErrorCode::AccountNotFound(address)) if *address == sysvar::clock::id()
+        ));
+    }
+
+    #[test]
+    fn test_swap_leg_and_account_metas() {
+        let mut keyed_account = keyed_account(clmmpool_account_data(60), SWAP_PROGRAM_ID);
+        *keyed_account.tick_array_map = zeroed_account_data::<TickArrayMap>();
+        let mut amm = CremaClmm::from_keyed_account(&keyed_account).unwrap();
+
+        let swap_params = SwapParams {
+            source_mint: Pubkey::default(),
+            destination_mint: Pubkey::new_unique(),
+            user_source_token_account: Pubkey::new_unique(),
+            user_destination_token_account: Pubkey::new_unique(),
+            user_transfer_authority: Pubkey::new_unique(),
+            open_order_address: None,
+            quote_mint_to_referrer: None,
+            in_amount: 100,
+        };
+        let err = amm
+            .get_swap_leg_and_account_metas(&swap_params)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<ErrorCode>(),
+            Some(ErrorCode::PartnerNotSet)
+        ));
+
+        let partner = Pubkey::new_unique();
+        amm.set_partner(Some(partner));
+        let swap_leg_and_account_metas = amm.get_swap_leg_and_account_metas(&swap_params).unwrap();
+        let account_metas = &swap_leg_and_account_metas.account_metas;
+        assert_eq!(account_metas.len(), 15);
+        assert_eq!(account_metas[0].pubkey, SWAP_PROGRAM_ID);
+        assert_eq!(account_metas[2].pubkey, keyed_account.key);
+        assert_eq!(
+            account_metas[5].pubkey,
+            swap_params.user_source_token_account
+        );
+        assert!(account_metas[10].is_signer);
+        assert_eq!(account_metas[11].pubkey, partner);
+
+        let err = build_route_instruction(
+            amm.get_swap_leg_and_account_metas(&swap_params).unwrap(),
+            &swap_params,
+            90,
+            50,
+            10,
+            None,
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<ErrorCode>(),
+            Some(ErrorCode::InvalidPlatformFeeAccount)
+        ));
+        let ix = build_route_instruction(swap_leg_and_account_metas, &swap_params, 90, 50, 0, None)
+            .unwrap();
+        assert_eq!(ix.program_id, jupiter::ID);
+        assert_eq!(ix.accounts.len(), 3 + 15);
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            swap_params.user_destination_token_account
+        );
+        assert!(!ix.accounts[2].is_writable);
+        assert_eq!(&ix.data[..8], &[229, 23, 203, 151, 122, 227, 173, 42]);
+    }
 
     #[test]
     fn test_quote() {
@@ -278,12 +793,26 @@ mod tests {
 
         let quote = amm
             .quote(&QuoteParams {
-                in_amount: 6000000000000,
+                amount: 6000000000000,
                 input_mint: token_b,
                 output_mint: token_a,
+                swap_mode: SwapMode::ExactIn,
+                sqrt_price_limit: None,
             })
             .unwrap();
 
         println!("quote: {:?}", quote);
+
+        let quote = amm
+            .quote(&QuoteParams {
+                amount: 1000000000,
+                input_mint: token_b,
+                output_mint: token_a,
+                swap_mode: SwapMode::ExactOut,
+                sqrt_price_limit: None,
+            })
+            .unwrap();
+
+        println!("exact out quote: {:?}", quote);
     }
 }
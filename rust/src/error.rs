@@ -1,3 +1,4 @@
+use solana_sdk::pubkey::Pubkey;
 use thiserror::Error;
 
 /// `ErrorCode` is the error type for the `clmmpool` program.
@@ -132,4 +133,58 @@ pub enum ErrorCode {
 
     #[error("Invalid fixed token type")]
     InvalidFixedTokenType,
+
+    // account loading
+    #[error("Account {0} not found")]
+    AccountNotFound(Pubkey),
+
+    #[error("Invalid size of account {address}, expected at least {expected} bytes, got {actual}")]
+    InvalidAccountSize {
+        address: Pubkey,
+        expected: usize,
+        actual: usize,
+    },
+
+    #[error("Invalid discriminator of account {0}")]
+    InvalidAccountDiscriminator(Pubkey),
+
+    #[error("Failed to deserialize account {0}")]
+    AccountDeserializeFailed(Pubkey),
+
+    #[error("Unknown program owner {0}")]
+    UnknownProgramOwner(Pubkey),
+
+    #[error("Invalid amm params, {0}")]
+    InvalidAmmParams(String),
+
+    #[error("{0} tick arrays are not loaded yet")]
+    TickArraysNotLoaded(usize),
+
+    // swap
+    #[error("Partner is not set")]
+    PartnerNotSet,
+
+    #[error("Invalid Jupiter route instruction")]
+    InvalidRouteData,
+
+    #[error("The platform fee account has to be set exactly when the platform fee is not zero")]
+    InvalidPlatformFeeAccount,
+
+    // pair
+    #[error("Invalid price change, expected between 0 and 1 exclusive")]
+    InvalidPriceChange,
+
+    // rewarder
+    #[error("Invalid rewarder index {0}")]
+    InvalidRewarderIndex(u8),
+
+    #[error("Rewarder {0} is not initialized")]
+    RewarderNotInitialized(u8),
+
+    #[error("Rewarder {0} is already initialized")]
+    RewarderAlreadyInitialized(u8),
+
+    // position
+    #[error("Owed amount overflow")]
+    OwedAmountOverflow,
 }
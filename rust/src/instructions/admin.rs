@@ -0,0 +1,308 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::{system_program, sysvar};
+use solana_sdk::pubkey::Pubkey;
+
+use super::instruction_data;
+use crate::state::clmm_config::ClmmConfig;
+use crate::state::clmmpool::Clmmpool;
+use crate::state::fee_tier::FeeTier;
+use crate::utils::pda::get_associated_token_address;
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct InitializeClmmConfigArgs {
+    protocol_authority: Pubkey,
+    protocol_fee_claim_authority: Pubkey,
+    create_pool_authority: Pubkey,
+    protocol_fee_rate: u16,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct CreateFeeTierArgs {
+    tick_spacing: u16,
+    fee_rate: u16,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct UpdateConfigArgs {
+    new_protocol_fee_rate: Option<u16>,
+    create_pool_authority: Option<Pubkey>,
+    claim_authority: Option<Pubkey>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct UpdateFeeRateArgs {
+    new_fee_rate: u16,
+}
+
+/// Creates the [ClmmConfig] of `program_id`.
+pub fn initialize_clmm_config(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    protocol_authority: &Pubkey,
+    protocol_fee_claim_authority: &Pubkey,
+    create_pool_authority: &Pubkey,
+    protocol_fee_rate: u16,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(ClmmConfig::find_address(program_id), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data(
+            "initialize_clmm_config",
+            &InitializeClmmConfigArgs {
+                protocol_authority: *protocol_authority,
+                protocol_fee_claim_authority: *protocol_fee_claim_authority,
+                create_pool_authority: *create_pool_authority,
+                protocol_fee_rate,
+            },
+        ),
+    }
+}
+
+/// Creates the [FeeTier] of `tick_spacing`.
+pub fn create_fee_tier(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    protocol_authority: &Pubkey,
+    tick_spacing: u16,
+    fee_rate: u16,
+) -> Instruction {
+    let clmm_config = ClmmConfig::find_address(program_id);
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(clmm_config, false),
+        AccountMeta::new(
+            FeeTier::find_address(&clmm_config, tick_spacing, program_id),
+            false,
+        ),
+        AccountMeta::new_readonly(*protocol_authority, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data(
+            "create_fee_tier",
+            &CreateFeeTierArgs {
+                tick_spacing,
+                fee_rate,
+            },
+        ),
+    }
+}
+
+/// Updates the [ClmmConfig], `None` keeps the current value.
+pub fn update_config(
+    program_id: &Pubkey,
+    protocol_authority: &Pubkey,
+    new_protocol_fee_rate: Option<u16>,
+    create_pool_authority: Option<Pubkey>,
+    claim_authority: Option<Pubkey>,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(ClmmConfig::find_address(program_id), false),
+        AccountMeta::new_readonly(*protocol_authority, true),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data(
+            "update_config",
+            &UpdateConfigArgs {
+                new_protocol_fee_rate,
+                create_pool_authority,
+                claim_authority,
+            },
+        ),
+    }
+}
+
+/// Updates the fee rate of the clmmpool, the denominator is 1_000_000.
+pub fn update_fee_rate(
+    program_id: &Pubkey,
+    protocol_authority: &Pubkey,
+    clmmpool: &Pubkey,
+    new_fee_rate: u16,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*protocol_authority, true),
+        AccountMeta::new_readonly(ClmmConfig::find_address(program_id), false),
+        AccountMeta::new(*clmmpool, false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data("update_fee_rate", &UpdateFeeRateArgs { new_fee_rate }),
+    }
+}
+
+/// Starts transferring the protocol authority, it takes effect once `new_authority` accepts it.
+pub fn transfer_protocol_authority(
+    program_id: &Pubkey,
+    protocol_authority: &Pubkey,
+    new_authority: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*protocol_authority, true),
+        AccountMeta::new(ClmmConfig::find_address(program_id), false),
+        AccountMeta::new_readonly(*new_authority, false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data("transfer_protocol_authority", &()),
+    }
+}
+
+/// Accepts the protocol authority transferred to `new_authority`.
+pub fn accept_protocol_authority(program_id: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*new_authority, true),
+        AccountMeta::new(ClmmConfig::find_address(program_id), false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data("accept_protocol_authority", &()),
+    }
+}
+
+/// Collects the protocol fee owed by the clmmpool into the token accounts of the claim authority.
+pub fn collect_protocol_fee(
+    program_id: &Pubkey,
+    protocol_fee_claim_authority: &Pubkey,
+    clmmpool: &Pubkey,
+    clmmpool_info: &Clmmpool,
+    token_a_account: &Pubkey,
+    token_b_account: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*protocol_fee_claim_authority, true),
+        AccountMeta::new_readonly(ClmmConfig::find_address(program_id), false),
+        AccountMeta::new(*clmmpool, false),
+        AccountMeta::new(*token_a_account, false),
+        AccountMeta::new(*token_b_account, false),
+        AccountMeta::new(clmmpool_info.token_a_vault, false),
+        AccountMeta::new(clmmpool_info.token_b_vault, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data("collect_protocol_fee", &()),
+    }
+}
+
+/// Collects the protocol fee of every clmmpool owing some, see [Clmmpool::protocol_fee_owed],
+/// into the associated token accounts of the claim authority.
+pub fn collect_protocol_fees(
+    program_id: &Pubkey,
+    protocol_fee_claim_authority: &Pubkey,
+    clmmpools: &[(Pubkey, Clmmpool)],
+) -> Vec<Instruction> {
+    clmmpools
+        .iter()
+        .filter(|(_, clmmpool_info)| clmmpool_info.protocol_fee_owed() != (0, 0))
+        .map(|(clmmpool, clmmpool_info)| {
+            collect_protocol_fee(
+                program_id,
+                protocol_fee_claim_authority,
+                clmmpool,
+                clmmpool_info,
+                &get_associated_token_address(protocol_fee_claim_authority, &clmmpool_info.token_a),
+                &get_associated_token_address(protocol_fee_claim_authority, &clmmpool_info.token_b),
+            )
+        })
+        .collect()
+}
+
+/// Pauses the clmmpool.
+pub fn pause_clmmpool(
+    program_id: &Pubkey,
+    protocol_authority: &Pubkey,
+    clmmpool: &Pubkey,
+) -> Instruction {
+    pause_instruction(program_id, "pause_clmmpool", protocol_authority, clmmpool)
+}
+
+/// Unpauses the clmmpool.
+pub fn unpause_clmmpool(
+    program_id: &Pubkey,
+    protocol_authority: &Pubkey,
+    clmmpool: &Pubkey,
+) -> Instruction {
+    pause_instruction(program_id, "unpause_clmmpool", protocol_authority, clmmpool)
+}
+
+fn pause_instruction(
+    program_id: &Pubkey,
+    name: &str,
+    protocol_authority: &Pubkey,
+    clmmpool: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(ClmmConfig::find_address(program_id), false),
+        AccountMeta::new_readonly(*protocol_authority, true),
+        AccountMeta::new(*clmmpool, false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data(name, &()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::swap_with_partner::SWAP_PROGRAM_ID;
+
+    #[test]
+    fn test_collect_protocol_fees() {
+        let claim_authority = Pubkey::new_unique();
+        let owing = Clmmpool {
+            token_a: Pubkey::new_unique(),
+            token_b: Pubkey::new_unique(),
+            fee_protocol_token_b: 10,
+            ..Default::default()
+        };
+        let clmmpools = [
+            (Pubkey::new_unique(), owing),
+            (Pubkey::new_unique(), Clmmpool::default()),
+        ];
+
+        let ixs = collect_protocol_fees(&SWAP_PROGRAM_ID, &claim_authority, &clmmpools);
+        assert_eq!(ixs.len(), 1);
+        assert_eq!(ixs[0].accounts[2].pubkey, clmmpools[0].0);
+        assert_eq!(
+            ixs[0].accounts[4].pubkey,
+            get_associated_token_address(&claim_authority, &owing.token_b)
+        );
+
+        assert_eq!(
+            ixs[0].accounts[1].pubkey,
+            ClmmConfig::find_address(&SWAP_PROGRAM_ID)
+        );
+
+        // A fork derives its accounts from its own program id.
+        let fork = Pubkey::new_unique();
+        let ix = create_fee_tier(&fork, &claim_authority, &claim_authority, 60, 3000);
+        let clmm_config = ClmmConfig::find_address(&fork);
+        assert_eq!(ix.program_id, fork);
+        assert_eq!(ix.accounts[1].pubkey, clmm_config);
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            FeeTier::find_address(&clmm_config, 60, &fork)
+        );
+        let ix = pause_clmmpool(&fork, &claim_authority, &clmmpools[0].0);
+        assert_eq!(ix.program_id, fork);
+        assert_eq!(ix.accounts[0].pubkey, clmm_config);
+    }
+}
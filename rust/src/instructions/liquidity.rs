@@ -0,0 +1,441 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::{system_program, sysvar};
+use solana_sdk::pubkey::Pubkey;
+
+use super::instruction_data;
+use crate::state::clmmpool::Clmmpool;
+use crate::state::clmmpool_metadata::ClmmpoolMetadata;
+use crate::state::position::Position;
+use crate::state::tick_array::TickArray;
+use crate::state::tick_array_map::TickArrayMap;
+use crate::utils::pda::{
+    find_position_edition_address, find_position_metadata_address, get_associated_token_address,
+    ASSOCIATED_TOKEN_PROGRAM_ID, METADATA_PROGRAM_ID,
+};
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct OpenPositionArgs {
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct IncreaseLiquidityArgs {
+    delta_liquidity: u128,
+    token_a_max: u64,
+    token_b_max: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct IncreaseLiquidityWithFixedTokenArgs {
+    token_a: u64,
+    token_b: u64,
+    is_a_fixed: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct DecreaseLiquidityArgs {
+    delta_liquidity: u128,
+    token_a_min: u64,
+    token_b_min: u64,
+}
+
+/// The tick array holding `tick_index` in the clmmpool.
+pub fn tick_array_address(
+    program_id: &Pubkey,
+    clmmpool: &Pubkey,
+    clmmpool_info: &Clmmpool,
+    tick_index: i32,
+) -> Pubkey {
+    TickArray::find_address(
+        clmmpool,
+        TickArray::array_index(tick_index, clmmpool_info.tick_spacing),
+        program_id,
+    )
+}
+
+/// Opens a position in `[tick_lower_index, tick_upper_index)`, `position_nft_mint` is a new
+/// keypair that has to sign the transaction.
+///
+/// Pools with a [ClmmpoolMetadata] account pass it so the position nft gets the pool's
+/// name, symbol and uri.
+pub fn open_position(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    clmmpool: &Pubkey,
+    position_nft_mint: &Pubkey,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    has_clmmpool_metadata: bool,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new_readonly(*clmmpool, false),
+        AccountMeta::new(Position::find_address(position_nft_mint, program_id), false),
+        AccountMeta::new(*position_nft_mint, true),
+        AccountMeta::new(find_position_metadata_address(position_nft_mint), false),
+        AccountMeta::new(find_position_edition_address(position_nft_mint), false),
+        AccountMeta::new(
+            get_associated_token_address(owner, position_nft_mint),
+            false,
+        ),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(METADATA_PROGRAM_ID, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    if has_clmmpool_metadata {
+        accounts.push(AccountMeta::new_readonly(
+            ClmmpoolMetadata::find_address(clmmpool, program_id),
+            false,
+        ));
+    }
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data(
+            "open_position",
+            &OpenPositionArgs {
+                tick_lower_index,
+                tick_upper_index,
+            },
+        ),
+    }
+}
+
+/// Adds `delta_liquidity` to the position, paying at most `token_a_max` and `token_b_max`.
+#[allow(clippy::too_many_arguments)]
+pub fn increase_liquidity(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    clmmpool: &Pubkey,
+    clmmpool_info: &Clmmpool,
+    position: &Position,
+    token_a_account: &Pubkey,
+    token_b_account: &Pubkey,
+    delta_liquidity: u128,
+    token_a_max: u64,
+    token_b_max: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: liquidity_accounts(
+            program_id,
+            owner,
+            true,
+            clmmpool,
+            clmmpool_info,
+            position,
+            token_a_account,
+            token_b_account,
+        ),
+        data: instruction_data(
+            "increase_liquidity",
+            &IncreaseLiquidityArgs {
+                delta_liquidity,
+                token_a_max,
+                token_b_max,
+            },
+        ),
+    }
+}
+
+/// Adds liquidity by the amount of one token, `token_a` if `is_a_fixed` else `token_b`, the
+/// other amount is the maximum to pay.
+#[allow(clippy::too_many_arguments)]
+pub fn increase_liquidity_with_fixed_token(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    clmmpool: &Pubkey,
+    clmmpool_info: &Clmmpool,
+    position: &Position,
+    token_a_account: &Pubkey,
+    token_b_account: &Pubkey,
+    token_a: u64,
+    token_b: u64,
+    is_a_fixed: bool,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: liquidity_accounts(
+            program_id,
+            owner,
+            true,
+            clmmpool,
+            clmmpool_info,
+            position,
+            token_a_account,
+            token_b_account,
+        ),
+        data: instruction_data(
+            "increase_liquidity_with_fixed_token",
+            &IncreaseLiquidityWithFixedTokenArgs {
+                token_a,
+                token_b,
+                is_a_fixed,
+            },
+        ),
+    }
+}
+
+/// Removes `delta_liquidity` from the position, receiving at least `token_a_min` and
+/// `token_b_min`.
+#[allow(clippy::too_many_arguments)]
+pub fn decrease_liquidity(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    clmmpool: &Pubkey,
+    clmmpool_info: &Clmmpool,
+    position: &Position,
+    token_a_account: &Pubkey,
+    token_b_account: &Pubkey,
+    delta_liquidity: u128,
+    token_a_min: u64,
+    token_b_min: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: liquidity_accounts(
+            program_id,
+            owner,
+            false,
+            clmmpool,
+            clmmpool_info,
+            position,
+            token_a_account,
+            token_b_account,
+        ),
+        data: instruction_data(
+            "decrease_liquidity",
+            &DecreaseLiquidityArgs {
+                delta_liquidity,
+                token_a_min,
+                token_b_min,
+            },
+        ),
+    }
+}
+
+/// Collects the fee owed to the position.
+pub fn collect_fee(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    clmmpool: &Pubkey,
+    clmmpool_info: &Clmmpool,
+    position: &Position,
+    token_a_account: &Pubkey,
+    token_b_account: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new_readonly(*clmmpool, false),
+        AccountMeta::new(
+            Position::find_address(&position.position_nft_mint, program_id),
+            false,
+        ),
+        AccountMeta::new_readonly(
+            get_associated_token_address(owner, &position.position_nft_mint),
+            false,
+        ),
+        AccountMeta::new(*token_a_account, false),
+        AccountMeta::new(*token_b_account, false),
+        AccountMeta::new(clmmpool_info.token_a_vault, false),
+        AccountMeta::new(clmmpool_info.token_b_vault, false),
+        AccountMeta::new_readonly(
+            tick_array_address(
+                program_id,
+                clmmpool,
+                clmmpool_info,
+                position.tick_lower_index,
+            ),
+            false,
+        ),
+        AccountMeta::new_readonly(
+            tick_array_address(
+                program_id,
+                clmmpool,
+                clmmpool_info,
+                position.tick_upper_index,
+            ),
+            false,
+        ),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data("collect_fee", &()),
+    }
+}
+
+/// Closes an empty position and burns its nft, see [Position::is_empty].
+pub fn remove_position(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    position_nft_mint: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(Position::find_address(position_nft_mint, program_id), false),
+        AccountMeta::new(*position_nft_mint, false),
+        AccountMeta::new(
+            get_associated_token_address(owner, position_nft_mint),
+            false,
+        ),
+        AccountMeta::new(find_position_metadata_address(position_nft_mint), false),
+        AccountMeta::new(find_position_edition_address(position_nft_mint), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(METADATA_PROGRAM_ID, false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data("remove_position", &()),
+    }
+}
+
+/// The accounts shared by the increase and decrease liquidity instructions.
+#[allow(clippy::too_many_arguments)]
+fn liquidity_accounts(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    is_owner_writable: bool,
+    clmmpool: &Pubkey,
+    clmmpool_info: &Clmmpool,
+    position: &Position,
+    token_a_account: &Pubkey,
+    token_b_account: &Pubkey,
+) -> Vec<AccountMeta> {
+    let owner_meta = if is_owner_writable {
+        AccountMeta::new(*owner, true)
+    } else {
+        AccountMeta::new_readonly(*owner, true)
+    };
+    vec![
+        owner_meta,
+        AccountMeta::new(*clmmpool, false),
+        AccountMeta::new(
+            Position::find_address(&position.position_nft_mint, program_id),
+            false,
+        ),
+        AccountMeta::new_readonly(
+            get_associated_token_address(owner, &position.position_nft_mint),
+            false,
+        ),
+        AccountMeta::new(*token_a_account, false),
+        AccountMeta::new(*token_b_account, false),
+        AccountMeta::new(clmmpool_info.token_a_vault, false),
+        AccountMeta::new(clmmpool_info.token_b_vault, false),
+        AccountMeta::new(
+            tick_array_address(
+                program_id,
+                clmmpool,
+                clmmpool_info,
+                position.tick_lower_index,
+            ),
+            false,
+        ),
+        AccountMeta::new(
+            tick_array_address(
+                program_id,
+                clmmpool,
+                clmmpool_info,
+                position.tick_upper_index,
+            ),
+            false,
+        ),
+        AccountMeta::new(TickArrayMap::find_address(clmmpool, program_id), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::swap_with_partner::SWAP_PROGRAM_ID;
+
+    #[test]
+    fn test_liquidity_instructions() {
+        let owner = Pubkey::new_unique();
+        let clmmpool = Pubkey::new_unique();
+        let position_nft_mint = Pubkey::new_unique();
+        let clmmpool_info = Clmmpool {
+            token_a_vault: Pubkey::new_unique(),
+            token_b_vault: Pubkey::new_unique(),
+            tick_spacing: 60,
+            ..Default::default()
+        };
+        let position = Position {
+            clmmpool,
+            position_nft_mint,
+            tick_lower_index: -6000,
+            tick_upper_index: 6000,
+            ..Default::default()
+        };
+        let position_address = Position::find_address(&position_nft_mint, &SWAP_PROGRAM_ID);
+        let position_ata = get_associated_token_address(&owner, &position_nft_mint);
+
+        let ix = open_position(
+            &SWAP_PROGRAM_ID,
+            &owner,
+            &clmmpool,
+            &position_nft_mint,
+            -6000,
+            6000,
+            true,
+        );
+        assert_eq!(ix.accounts.len(), 13);
+        assert_eq!(ix.accounts[2].pubkey, position_address);
+        assert!(ix.accounts[3].is_signer);
+        assert_eq!(ix.accounts[6].pubkey, position_ata);
+        assert_eq!(
+            OpenPositionArgs::try_from_slice(&ix.data[8..]).unwrap(),
+            OpenPositionArgs {
+                tick_lower_index: -6000,
+                tick_upper_index: 6000,
+            }
+        );
+
+        let ix = decrease_liquidity(
+            &SWAP_PROGRAM_ID,
+            &owner,
+            &clmmpool,
+            &clmmpool_info,
+            &position,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            1,
+            0,
+            0,
+        );
+        assert!(!ix.accounts[0].is_writable);
+        assert_eq!(ix.accounts[2].pubkey, position_address);
+        assert_eq!(ix.accounts[3].pubkey, position_ata);
+        let array_index = |tick_index| TickArray::array_index(tick_index, 60);
+        assert_eq!(
+            ix.accounts[8].pubkey,
+            TickArray::find_address(&clmmpool, array_index(-6000), &SWAP_PROGRAM_ID)
+        );
+        assert_eq!(
+            ix.accounts[9].pubkey,
+            TickArray::find_address(&clmmpool, array_index(6000), &SWAP_PROGRAM_ID)
+        );
+
+        let ix = remove_position(&SWAP_PROGRAM_ID, &owner, &position_nft_mint);
+        assert_eq!(
+            ix.data,
+            crate::utils::sighash::sighash("global", "remove_position")
+        );
+
+        // A fork derives its accounts from its own program id.
+        let fork = Pubkey::new_unique();
+        let ix = remove_position(&fork, &owner, &position_nft_mint);
+        assert_eq!(ix.program_id, fork);
+        assert_eq!(
+            ix.accounts[1].pubkey,
+            Position::find_address(&position_nft_mint, &fork)
+        );
+    }
+}
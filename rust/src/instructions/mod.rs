@@ -1 +1,19 @@
+use borsh::BorshSerialize;
+
+use crate::utils::sighash;
+
+pub mod admin;
+pub mod liquidity;
+pub mod partner;
+pub mod pool;
+pub mod rewarder;
+pub mod swap;
 pub mod swap_with_partner;
+pub mod tick_array;
+
+/// The anchor instruction data, the discriminator of `name` followed by the borsh encoded args.
+pub(crate) fn instruction_data<T: BorshSerialize>(name: &str, args: &T) -> Vec<u8> {
+    let mut data = sighash::sighash("global", name).to_vec();
+    data.append(&mut args.try_to_vec().unwrap());
+    data
+}
@@ -0,0 +1,236 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::{system_program, sysvar};
+use solana_sdk::pubkey::Pubkey;
+
+use super::instruction_data;
+use crate::state::clmm_config::ClmmConfig;
+use crate::state::clmmpool::Clmmpool;
+use crate::state::partner::Partner;
+use crate::utils::pda::get_associated_token_address;
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct CreatePartnerArgs {
+    partner_fee_claim_authority: Pubkey,
+    fee_rate: u16,
+    start_time: u64,
+    end_time: u64,
+    name: String,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct UpdatePartnerArgs {
+    new_fee_rate: Option<u16>,
+    new_claim_authority: Option<Pubkey>,
+    start_time: Option<u64>,
+    end_time: Option<u64>,
+}
+
+/// The partner fee vault of `mint`, the associated token account of the partner.
+pub fn partner_fee_vault(partner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(partner, mint)
+}
+
+/// Creates the partner derived from `base`, see [Partner::find_address]. Both the protocol
+/// authority and `base` have to sign.
+#[allow(clippy::too_many_arguments)]
+pub fn create_partner(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    protocol_authority: &Pubkey,
+    base: &Pubkey,
+    partner_fee_claim_authority: &Pubkey,
+    fee_rate: u16,
+    start_time: u64,
+    end_time: u64,
+    name: String,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(ClmmConfig::find_address(program_id), false),
+        AccountMeta::new_readonly(*protocol_authority, true),
+        AccountMeta::new_readonly(*base, true),
+        AccountMeta::new(Partner::find_address(base, program_id), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data(
+            "create_partner",
+            &CreatePartnerArgs {
+                partner_fee_claim_authority: *partner_fee_claim_authority,
+                fee_rate,
+                start_time,
+                end_time,
+                name,
+            },
+        ),
+    }
+}
+
+/// Updates the partner, `None` keeps the current value.
+pub fn update_partner(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    partner: &Pubkey,
+    new_fee_rate: Option<u16>,
+    new_claim_authority: Option<Pubkey>,
+    start_time: Option<u64>,
+    end_time: Option<u64>,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(ClmmConfig::find_address(program_id), false),
+        AccountMeta::new(*partner, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data(
+            "update_partner",
+            &UpdatePartnerArgs {
+                new_fee_rate,
+                new_claim_authority,
+                start_time,
+                end_time,
+            },
+        ),
+    }
+}
+
+/// Collects the partner fee of the clmmpool from the partner fee vaults into the token accounts
+/// of the claim authority.
+pub fn collect_partner_fee(
+    program_id: &Pubkey,
+    partner_fee_claim_authority: &Pubkey,
+    partner: &Pubkey,
+    clmmpool: &Pubkey,
+    clmmpool_info: &Clmmpool,
+    token_a_account: &Pubkey,
+    token_b_account: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*partner_fee_claim_authority, true),
+        AccountMeta::new_readonly(*partner, false),
+        AccountMeta::new_readonly(*clmmpool, false),
+        AccountMeta::new(*token_a_account, false),
+        AccountMeta::new(*token_b_account, false),
+        AccountMeta::new(partner_fee_vault(partner, &clmmpool_info.token_a), false),
+        AccountMeta::new(partner_fee_vault(partner, &clmmpool_info.token_b), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data("collect_partner_fee", &()),
+    }
+}
+
+/// Starts transferring the claim authority, it takes effect once `new_authority` accepts it.
+pub fn transfer_partner_claim_authority(
+    program_id: &Pubkey,
+    partner_claim_authority: &Pubkey,
+    partner: &Pubkey,
+    new_authority: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*partner_claim_authority, true),
+        AccountMeta::new(*partner, false),
+        AccountMeta::new_readonly(*new_authority, false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data("transfer_partner_claim_authority", &()),
+    }
+}
+
+/// Accepts the claim authority transferred to `new_authority`.
+pub fn accept_partner_claim_authority(
+    program_id: &Pubkey,
+    new_authority: &Pubkey,
+    partner: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*new_authority, true),
+        AccountMeta::new(*partner, false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data("accept_partner_claim_authority", &()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partner_instructions() {
+        // Any deployment of the program derives its own config and partner accounts.
+        let program_id = Pubkey::new_unique();
+        let base = Pubkey::new_unique();
+        let claim_authority = Pubkey::new_unique();
+        let partner = Partner::find_address(&base, &program_id);
+
+        let ix = create_partner(
+            &program_id,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &base,
+            &claim_authority,
+            2000,
+            1,
+            2,
+            "partner".to_string(),
+        );
+        assert_eq!(ix.program_id, program_id);
+        assert_eq!(ix.accounts[1].pubkey, ClmmConfig::find_address(&program_id));
+        assert_eq!(ix.accounts[4].pubkey, partner);
+        assert!(ix.accounts[3].is_signer);
+        let args = CreatePartnerArgs::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(args.partner_fee_claim_authority, claim_authority);
+        assert_eq!(args.name, "partner");
+
+        let ix = update_partner(
+            &program_id,
+            &claim_authority,
+            &partner,
+            Some(1000),
+            None,
+            None,
+            Some(3),
+        );
+        assert_eq!(
+            UpdatePartnerArgs::try_from_slice(&ix.data[8..]).unwrap(),
+            UpdatePartnerArgs {
+                new_fee_rate: Some(1000),
+                new_claim_authority: None,
+                start_time: None,
+                end_time: Some(3),
+            }
+        );
+
+        let clmmpool_info = Clmmpool {
+            token_a: Pubkey::new_unique(),
+            token_b: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let ix = collect_partner_fee(
+            &program_id,
+            &claim_authority,
+            &partner,
+            &Pubkey::new_unique(),
+            &clmmpool_info,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+        );
+        assert_eq!(
+            ix.accounts[5].pubkey,
+            get_associated_token_address(&partner, &clmmpool_info.token_a)
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use rust_decimal::Decimal;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::{system_program, sysvar};
+use solana_sdk::pubkey::Pubkey;
+
+use super::instruction_data;
+use crate::error::ErrorCode;
+use crate::math::sqrt_price::SqrtPrice;
+use crate::math::tick_math::get_tick_at_sqrt_price;
+use crate::state::clmm_config::ClmmConfig;
+use crate::state::clmmpool::Clmmpool;
+use crate::state::clmmpool_metadata::ClmmpoolMetadata;
+use crate::state::fee_tier::FeeTier;
+use crate::state::tick_array::TickArray;
+use crate::state::tick_array_map::TickArrayMap;
+use crate::utils::pda::{get_associated_token_address, ASSOCIATED_TOKEN_PROGRAM_ID};
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct CreateClmmpoolArgs {
+    init_sqrt_price: u128,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct CreateTickArrayArgs {
+    array_index: u16,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct CreateClmmpoolMetadataArgs {
+    name: String,
+    uri: String,
+}
+
+/// The position nft metadata of a new clmmpool, creating it needs the protocol authority.
+#[derive(Debug, Clone)]
+pub struct ClmmpoolMetadataParams {
+    pub protocol_authority: Pubkey,
+    pub name: String,
+    pub uri: String,
+}
+
+/// The vault of `mint`, the associated token account of the clmmpool.
+pub fn clmmpool_vault(clmmpool: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(clmmpool, mint)
+}
+
+/// Creates the clmmpool of `token_a` and `token_b`, the mints have to be in canonical order,
+/// see [Clmmpool::order_mints].
+pub fn create_clmmpool(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    token_a: &Pubkey,
+    token_b: &Pubkey,
+    tick_spacing: u16,
+    init_sqrt_price: u128,
+) -> Instruction {
+    let clmm_config = ClmmConfig::find_address(program_id);
+    let clmmpool = Clmmpool::find_address(&clmm_config, token_a, token_b, tick_spacing, program_id);
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(clmm_config, false),
+        AccountMeta::new_readonly(
+            FeeTier::find_address(&clmm_config, tick_spacing, program_id),
+            false,
+        ),
+        AccountMeta::new(clmmpool, false),
+        AccountMeta::new_readonly(*token_a, false),
+        AccountMeta::new_readonly(*token_b, false),
+        AccountMeta::new(clmmpool_vault(&clmmpool, token_a), false),
+        AccountMeta::new(clmmpool_vault(&clmmpool, token_b), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data("create_clmmpool", &CreateClmmpoolArgs { init_sqrt_price }),
+    }
+}
+
+pub fn create_tick_array_map(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    clmmpool: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*clmmpool, false),
+        AccountMeta::new(TickArrayMap::find_address(clmmpool, program_id), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data("create_tick_array_map", &()),
+    }
+}
+
+pub fn create_tick_array(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    clmmpool: &Pubkey,
+    array_index: u16,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*clmmpool, false),
+        AccountMeta::new(
+            TickArray::find_address(clmmpool, array_index, program_id),
+            false,
+        ),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data("create_tick_array", &CreateTickArrayArgs { array_index }),
+    }
+}
+
+pub fn create_clmmpool_metadata(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    protocol_authority: &Pubkey,
+    clmmpool: &Pubkey,
+    name: String,
+    uri: String,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(ClmmConfig::find_address(program_id), false),
+        AccountMeta::new_readonly(*clmmpool, false),
+        AccountMeta::new(ClmmpoolMetadata::find_address(clmmpool, program_id), false),
+        AccountMeta::new_readonly(*protocol_authority, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data(
+            "create_clmmpool_metadata",
+            &CreateClmmpoolMetadataArgs { name, uri },
+        ),
+    }
+}
+
+/// Builds the instructions launching the clmmpool of `mint_x` and `mint_y` in `fee_tier` at
+/// `price`, the ui price of `mint_x` in `mint_y`.
+///
+/// The instructions create the clmmpool, its tick array map, the tick array of the initial
+/// price and, given `metadata`, the clmmpool metadata. Returns the clmmpool address too.
+#[allow(clippy::too_many_arguments)]
+pub fn new_clmmpool(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    mint_x: &Pubkey,
+    mint_y: &Pubkey,
+    decimals_x: u8,
+    decimals_y: u8,
+    fee_tier: &FeeTier,
+    price: Decimal,
+    metadata: Option<ClmmpoolMetadataParams>,
+) -> Result<(Pubkey, Vec<Instruction>), ErrorCode> {
+    let (token_a, token_b) = Clmmpool::order_mints(mint_x, mint_y);
+    let init_sqrt_price = if token_a == *mint_x {
+        SqrtPrice::try_from_price(price, decimals_x, decimals_y)?
+    } else {
+        let price = Decimal::ONE
+            .checked_div(price)
+            .ok_or(ErrorCode::SqrtPriceOutOfBounds)?;
+        SqrtPrice::try_from_price(price, decimals_y, decimals_x)?
+    };
+
+    let tick_spacing = fee_tier.tick_spacing;
+    let clmm_config = ClmmConfig::find_address(program_id);
+    let clmmpool =
+        Clmmpool::find_address(&clmm_config, &token_a, &token_b, tick_spacing, program_id);
+    let array_index = TickArray::array_index(get_tick_at_sqrt_price(init_sqrt_price), tick_spacing);
+
+    let mut instructions = vec![
+        create_clmmpool(
+            program_id,
+            payer,
+            &token_a,
+            &token_b,
+            tick_spacing,
+            init_sqrt_price,
+        ),
+        create_tick_array_map(program_id, payer, &clmmpool),
+        create_tick_array(program_id, payer, &clmmpool, array_index),
+    ];
+    if let Some(metadata) = metadata {
+        instructions.push(create_clmmpool_metadata(
+            program_id,
+            payer,
+            &metadata.protocol_authority,
+            &clmmpool,
+            metadata.name,
+            metadata.uri,
+        ));
+    }
+    Ok((clmmpool, instructions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::swap_with_partner::SWAP_PROGRAM_ID;
+
+    #[test]
+    fn test_new_clmmpool() {
+        let payer = Pubkey::new_unique();
+        let (mint_x, mint_y) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let fee_tier = FeeTier {
+            fee_rate: 3000,
+            tick_spacing: 60,
+            bump: 0,
+        };
+
+        let (clmmpool, instructions) = new_clmmpool(
+            &SWAP_PROGRAM_ID,
+            &payer,
+            &mint_y,
+            &mint_x,
+            9,
+            6,
+            &fee_tier,
+            Decimal::new(20, 0),
+            None,
+        )
+        .unwrap();
+        assert_eq!(instructions.len(), 3);
+        // mint_x < mint_y, so the pool trades mint_x for mint_y at 1/20.
+        let sqrt_price = CreateClmmpoolArgs::try_from_slice(&instructions[0].data[8..])
+            .unwrap()
+            .init_sqrt_price;
+        let ui_price = SqrtPrice::new(sqrt_price).ui_price(6, 9);
+        assert!((ui_price - Decimal::new(5, 2)).abs() < Decimal::new(1, 8));
+        assert_eq!(instructions[0].accounts[3].pubkey, clmmpool);
+        assert_eq!(instructions[0].accounts[4].pubkey, mint_x);
+        assert_eq!(
+            clmmpool,
+            Clmmpool::find_address(
+                &ClmmConfig::find_address(&SWAP_PROGRAM_ID),
+                &mint_y,
+                &mint_x,
+                60,
+                &SWAP_PROGRAM_ID
+            )
+        );
+
+        let metadata = ClmmpoolMetadataParams {
+            protocol_authority: payer,
+            name: "Crema".to_string(),
+            uri: "https://crema.finance".to_string(),
+        };
+        let (_, instructions) = new_clmmpool(
+            &SWAP_PROGRAM_ID,
+            &payer,
+            &mint_x,
+            &mint_y,
+            6,
+            9,
+            &fee_tier,
+            Decimal::new(5, 2),
+            Some(metadata),
+        )
+        .unwrap();
+        assert_eq!(instructions.len(), 4);
+        assert_eq!(
+            CreateClmmpoolArgs::try_from_slice(&instructions[0].data[8..])
+                .unwrap()
+                .init_sqrt_price,
+            sqrt_price
+        );
+
+        assert!(matches!(
+            new_clmmpool(
+                &SWAP_PROGRAM_ID,
+                &payer,
+                &mint_x,
+                &mint_y,
+                6,
+                9,
+                &fee_tier,
+                Decimal::ZERO,
+                None
+            ),
+            Err(ErrorCode::SqrtPriceOutOfBounds)
+        ));
+        // Prices past the sqrt price range fail instead of panicking.
+        for price in [Decimal::MAX, Decimal::new(1, 28)] {
+            assert!(matches!(
+                new_clmmpool(
+                    &SWAP_PROGRAM_ID,
+                    &payer,
+                    &mint_x,
+                    &mint_y,
+                    6,
+                    9,
+                    &fee_tier,
+                    price,
+                    None
+                ),
+                Err(ErrorCode::SqrtPriceOutOfBounds)
+            ));
+        }
+    }
+}
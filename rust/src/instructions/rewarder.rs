@@ -0,0 +1,252 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::{system_program, sysvar};
+use solana_sdk::{pubkey, pubkey::Pubkey};
+
+use super::instruction_data;
+use super::liquidity::tick_array_address;
+use crate::error::ErrorCode;
+use crate::state::clmmpool::{Clmmpool, REWARDER_NUM};
+use crate::state::position::Position;
+use crate::utils::pda::get_associated_token_address;
+
+/// The mint wrapper program minting the rewards of the clmmpool rewarders.
+pub const MINT_WRAPPER_PROGRAM_ID: Pubkey = pubkey!("CMWr5a5feRxAeowwHcm1u5bYwmUvsAjcBbqLRcBshyAR");
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct InitializeRewarderArgs {
+    rewarder_index: u8,
+    mint_wrapper: Pubkey,
+    minter: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct UpdateRewarderEmissionArgs {
+    rewarder_index: u8,
+    emissions_per_second: u128,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct CollectRewarderArgs {
+    rewarder_index: u8,
+}
+
+/// Initializes the rewarder at `rewarder_index` minting `rewarder_token_mint` through the
+/// `mint_wrapper` and `minter` of the mint wrapper program.
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_rewarder(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    clmmpool: &Pubkey,
+    clmmpool_info: &Clmmpool,
+    rewarder_authority: &Pubkey,
+    rewarder_token_mint: &Pubkey,
+    rewarder_index: u8,
+    mint_wrapper: &Pubkey,
+    minter: &Pubkey,
+) -> Result<Instruction, ErrorCode> {
+    if rewarder_index as usize >= REWARDER_NUM {
+        return Err(ErrorCode::InvalidRewarderIndex(rewarder_index));
+    }
+    if clmmpool_info.rewarder_infos.0[rewarder_index as usize].is_initialized() {
+        return Err(ErrorCode::RewarderAlreadyInitialized(rewarder_index));
+    }
+
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(clmmpool_info.clmm_config, false),
+        AccountMeta::new(*clmmpool, false),
+        AccountMeta::new_readonly(*rewarder_authority, true),
+        AccountMeta::new_readonly(*rewarder_token_mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data(
+            "initialize_rewarder",
+            &InitializeRewarderArgs {
+                rewarder_index,
+                mint_wrapper: *mint_wrapper,
+                minter: *minter,
+            },
+        ),
+    })
+}
+
+/// Sets the emissions per second, Q64.64, of an initialized rewarder.
+pub fn update_rewarder_emission(
+    program_id: &Pubkey,
+    clmmpool: &Pubkey,
+    clmmpool_info: &Clmmpool,
+    rewarder_index: u8,
+    emissions_per_second: u128,
+) -> Result<Instruction, ErrorCode> {
+    let rewarder = clmmpool_info.get_rewarder(rewarder_index)?;
+
+    let accounts = vec![
+        AccountMeta::new_readonly(rewarder.authority, true),
+        AccountMeta::new_readonly(clmmpool_info.clmm_config, false),
+        AccountMeta::new(*clmmpool, false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data(
+            "update_rewarder_emission",
+            &UpdateRewarderEmissionArgs {
+                rewarder_index,
+                emissions_per_second,
+            },
+        ),
+    })
+}
+
+/// Collects the reward of an initialized rewarder owed to the position into the owner's
+/// associated token account of the reward mint.
+pub fn collect_rewarder(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    clmmpool: &Pubkey,
+    clmmpool_info: &Clmmpool,
+    position: &Position,
+    rewarder_index: u8,
+) -> Result<Instruction, ErrorCode> {
+    let rewarder = clmmpool_info.get_rewarder(rewarder_index)?;
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*clmmpool, false),
+        AccountMeta::new(
+            Position::find_address(&position.position_nft_mint, program_id),
+            false,
+        ),
+        AccountMeta::new_readonly(
+            get_associated_token_address(owner, &position.position_nft_mint),
+            false,
+        ),
+        AccountMeta::new(get_associated_token_address(owner, &rewarder.mint), false),
+        AccountMeta::new(rewarder.mint_wrapper, false),
+        AccountMeta::new(rewarder.minter, false),
+        AccountMeta::new_readonly(MINT_WRAPPER_PROGRAM_ID, false),
+        AccountMeta::new(rewarder.mint, false),
+        AccountMeta::new_readonly(
+            tick_array_address(
+                program_id,
+                clmmpool,
+                clmmpool_info,
+                position.tick_lower_index,
+            ),
+            false,
+        ),
+        AccountMeta::new_readonly(
+            tick_array_address(
+                program_id,
+                clmmpool,
+                clmmpool_info,
+                position.tick_upper_index,
+            ),
+            false,
+        ),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data("collect_rewarder", &CollectRewarderArgs { rewarder_index }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::clmmpool::Rewarder;
+
+    #[test]
+    fn test_rewarder_index() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let clmmpool = Pubkey::new_unique();
+        let mut clmmpool_info = Clmmpool {
+            tick_spacing: 60,
+            ..Default::default()
+        };
+        let rewarder = Rewarder {
+            mint_wrapper: Pubkey::new_unique(),
+            minter: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        clmmpool_info.rewarder_infos.0[0] = rewarder;
+        let position = Position {
+            position_nft_mint: Pubkey::new_unique(),
+            tick_lower_index: -600,
+            tick_upper_index: 600,
+            ..Default::default()
+        };
+
+        let ix =
+            collect_rewarder(&program_id, &owner, &clmmpool, &clmmpool_info, &position, 0).unwrap();
+        assert_eq!(
+            ix.accounts[4].pubkey,
+            get_associated_token_address(&owner, &rewarder.mint)
+        );
+        assert_eq!(ix.accounts[5].pubkey, rewarder.mint_wrapper);
+        assert_eq!(ix.accounts[6].pubkey, rewarder.minter);
+        assert_eq!(ix.accounts[8].pubkey, rewarder.mint);
+        assert_eq!(ix.program_id, program_id);
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            Position::find_address(&position.position_nft_mint, &program_id)
+        );
+
+        assert!(matches!(
+            collect_rewarder(&program_id, &owner, &clmmpool, &clmmpool_info, &position, 1),
+            Err(ErrorCode::RewarderNotInitialized(1))
+        ));
+        assert!(matches!(
+            update_rewarder_emission(
+                &program_id,
+                &clmmpool,
+                &clmmpool_info,
+                REWARDER_NUM as u8,
+                1
+            ),
+            Err(ErrorCode::InvalidRewarderIndex(3))
+        ));
+        let ix =
+            update_rewarder_emission(&program_id, &clmmpool, &clmmpool_info, 0, 1 << 64).unwrap();
+        assert_eq!(ix.accounts[0].pubkey, rewarder.authority);
+
+        let (mint_wrapper, minter) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(matches!(
+            initialize_rewarder(
+                &program_id,
+                &owner,
+                &clmmpool,
+                &clmmpool_info,
+                &owner,
+                &rewarder.mint,
+                0,
+                &mint_wrapper,
+                &minter,
+            ),
+            Err(ErrorCode::RewarderAlreadyInitialized(0))
+        ));
+        assert!(initialize_rewarder(
+            &program_id,
+            &owner,
+            &clmmpool,
+            &clmmpool_info,
+            &owner,
+            &rewarder.mint,
+            1,
+            &mint_wrapper,
+            &minter,
+        )
+        .is_ok());
+    }
+}
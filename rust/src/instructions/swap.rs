@@ -0,0 +1,147 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+
+use super::instruction_data;
+use crate::state::clmmpool::Clmmpool;
+use crate::state::tick_array::TickArray;
+use crate::state::tick_array_map::{TickArrayMap, MAX_SWAP_TICK_ARRAYS};
+
+#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
+pub struct SwapArgs {
+    a_to_b: bool,
+    by_amount_in: bool,
+    amount: u64,
+    amount_limit: u64,
+    sqrt_price_limit: u128,
+}
+
+/// Builds the `swap` instruction, the same as `swap_with_partner` without the partner accounts.
+#[allow(clippy::too_many_arguments)]
+pub fn swap(
+    program_id: &Pubkey,
+    clmmpool: &Pubkey,
+    clmmpool_info: &Clmmpool,
+    array_map: &TickArrayMap,
+    account_a: &Pubkey,
+    account_b: &Pubkey,
+    a_to_b: bool,
+    amount: u64,
+    amount_limit: u64,
+    sqrt_price_limit: u128,
+    by_amount_in: bool,
+    owner: Pubkey,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(clmmpool_info.clmm_config, false),
+        AccountMeta::new(*clmmpool, false),
+        AccountMeta::new_readonly(clmmpool_info.token_a, false),
+        AccountMeta::new_readonly(clmmpool_info.token_b, false),
+        AccountMeta::new(*account_a, false),
+        AccountMeta::new(*account_b, false),
+        AccountMeta::new(clmmpool_info.token_a_vault, false),
+        AccountMeta::new(clmmpool_info.token_b_vault, false),
+        AccountMeta::new(TickArrayMap::find_address(clmmpool, program_id), false),
+        AccountMeta::new_readonly(owner, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend(swap_tick_array_accounts(
+        program_id,
+        clmmpool,
+        clmmpool_info,
+        array_map,
+        a_to_b,
+    ));
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data(
+            "swap",
+            &SwapArgs {
+                a_to_b,
+                by_amount_in,
+                amount,
+                amount_limit,
+                sqrt_price_limit,
+            },
+        ),
+    }
+}
+
+/// The tick arrays a swap from the current pool price walks through, passed as remaining accounts.
+pub(crate) fn swap_tick_array_accounts(
+    program_id: &Pubkey,
+    clmmpool: &Pubkey,
+    clmmpool_info: &Clmmpool,
+    array_map: &TickArrayMap,
+    a_to_b: bool,
+) -> Vec<AccountMeta> {
+    let pool_array_index =
+        TickArray::array_index(clmmpool_info.current_tick_index, clmmpool_info.tick_spacing);
+    array_map
+        .swap_array_indexes(pool_array_index, a_to_b, MAX_SWAP_TICK_ARRAYS)
+        .into_iter()
+        .map(|array_index| {
+            AccountMeta::new(
+                TickArray::find_address(clmmpool, array_index, program_id),
+                false,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::sighash;
+
+    #[test]
+    fn test_swap() {
+        let clmmpool = Pubkey::new_unique();
+        let clmmpool_info = Clmmpool {
+            clmm_config: Pubkey::new_unique(),
+            token_a: Pubkey::new_unique(),
+            token_b: Pubkey::new_unique(),
+            token_a_vault: Pubkey::new_unique(),
+            token_b_vault: Pubkey::new_unique(),
+            tick_spacing: 60,
+            ..Default::default()
+        };
+        let mut array_map = TickArrayMap::default();
+        for array_index in [113usize, 115, 117] {
+            array_map.bitmap[array_index / 8] |= 1 << (array_index % 8);
+        }
+
+        // Any deployment of the program derives its own tick array accounts.
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let ix = swap(
+            &program_id,
+            &clmmpool,
+            &clmmpool_info,
+            &array_map,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            false,
+            1_000,
+            u64::MAX,
+            0,
+            false,
+            owner,
+        );
+
+        assert_eq!(ix.program_id, program_id);
+        assert_eq!(ix.accounts.len(), 11 + 2);
+        assert_eq!(ix.accounts[9].pubkey, owner);
+        assert!(ix.accounts[9].is_signer);
+        assert_eq!(ix.accounts[10].pubkey, spl_token::id());
+        assert_eq!(
+            ix.accounts[11].pubkey,
+            TickArray::find_address(&clmmpool, 115, &program_id)
+        );
+        assert_eq!(&ix.data[..8], &sighash::sighash("global", "swap"));
+        let args = SwapArgs::try_from_slice(&ix.data[8..]).unwrap();
+        assert!(!args.a_to_b && !args.by_amount_in);
+        assert_eq!(args.amount_limit, u64::MAX);
+    }
+}
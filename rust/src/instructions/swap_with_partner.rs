@@ -1,13 +1,14 @@
+use anyhow::Result;
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_client::rpc_client::RpcClient;
 use solana_program::instruction::{AccountMeta, Instruction};
 use solana_sdk::{pubkey, pubkey::Pubkey};
-use std::vec;
 
+use super::instruction_data;
+use super::swap::swap_tick_array_accounts;
 use crate::state::clmmpool::Clmmpool;
-use crate::state::tick_array::TickArray;
 use crate::state::tick_array_map::TickArrayMap;
-use crate::utils::sighash;
+use crate::state::AnchorAccount;
 
 pub const SWAP_PROGRAM_ID: Pubkey = pubkey!("CLMM9tUoggJu2wagPkkqs9eFG4BWhVBZWkP1qv3Sp7tR");
 
@@ -20,18 +21,15 @@ pub struct SwapWithPartnerArgs {
     sqrt_price_limit: u128,
 }
 
+/// Fetches the pool and its tick array map over RPC and builds the `swap_with_partner`
+/// instruction, see [swap_with_partner] for the offline version.
 #[allow(clippy::too_many_arguments)]
 pub fn new_swap_with_partner(
+    program_id: &Pubkey,
     rpc_client: &RpcClient,
-    clmm_config: &Pubkey,
     clmmpool: &Pubkey,
-    token_a: &Pubkey,
-    token_b: &Pubkey,
     account_a: &Pubkey,
     account_b: &Pubkey,
-    token_a_vault: &Pubkey,
-    token_b_vault: &Pubkey,
-    tick_array_map: &Pubkey,
     partner: &Pubkey,
     partner_ata_a: &Pubkey,
     partner_ata_b: &Pubkey,
@@ -41,68 +39,149 @@ pub fn new_swap_with_partner(
     sqrt_price_limit: u128,
     by_amount_in: bool,
     payer: Pubkey,
-) -> Instruction {
-    let data = &SwapWithPartnerArgs {
+) -> Result<Instruction> {
+    let data = rpc_client.get_account_data(clmmpool)?;
+    let clmmpool_info = Clmmpool::try_deserialize(clmmpool, &data)?;
+    let tick_array_map = TickArrayMap::find_address(clmmpool, program_id);
+    let tick_map_data = rpc_client.get_account_data(&tick_array_map)?;
+    let array_map = TickArrayMap::try_deserialize(&tick_array_map, &tick_map_data)?;
+
+    Ok(swap_with_partner(
+        program_id,
+        clmmpool,
+        &clmmpool_info,
+        &array_map,
+        account_a,
+        account_b,
+        partner,
+        partner_ata_a,
+        partner_ata_b,
         a_to_b,
-        by_amount_in,
         amount,
         amount_limit,
         sqrt_price_limit,
-    };
-
-    let mut dsa = data.try_to_vec().unwrap();
-    let mut distor = sighash::sighash("global", "swap_with_partner").to_vec();
-    distor.append(&mut dsa);
-
-    let data = rpc_client.get_account_data(clmmpool).unwrap();
-    let clmmpool_info: Clmmpool = Clmmpool::try_from_slice(&data[8..]).unwrap();
-    let tick_map_address = Clmmpool::get_tick_map_address(clmmpool, &SWAP_PROGRAM_ID);
-    let tick_map_data = rpc_client.get_account_data(&tick_map_address).unwrap();
-    let array_map: TickArrayMap = TickArrayMap::try_from_slice(&tick_map_data[8..]).unwrap();
-    let mut remaining_accounts: Vec<AccountMeta> = vec![];
-    let pool_array_index =
-        TickArray::array_index(clmmpool_info.current_tick_index, clmmpool_info.tick_spacing);
-    let mut expect_array_index = if array_map.is_seted(pool_array_index.into()) {
-        pool_array_index
-    } else {
-        array_map
-            .next_seted(pool_array_index.into(), a_to_b)
-            .unwrap() as u16
-    };
-
-    for _ in 0..3 {
-        let array_adderss =
-            TickArray::find_address(clmmpool, expect_array_index as u16, &SWAP_PROGRAM_ID);
+        by_amount_in,
+        payer,
+    ))
+}
 
-        remaining_accounts.push(AccountMeta::new(array_adderss, false));
-        let next_idx = array_map.next_seted(expect_array_index.into(), a_to_b);
-        if let Some(next_ix) = next_idx {
-            expect_array_index = next_ix as u16;
-        } else {
-            break;
-        }
-    }
-    println!("remaing accounts len:{}", remaining_accounts.len());
+/// Builds the `swap_with_partner` instruction from an already decoded pool and tick array map,
+/// e.g. the ones kept fresh by [crate::crema::CremaClmm].
+#[allow(clippy::too_many_arguments)]
+pub fn swap_with_partner(
+    program_id: &Pubkey,
+    clmmpool: &Pubkey,
+    clmmpool_info: &Clmmpool,
+    array_map: &TickArrayMap,
+    account_a: &Pubkey,
+    account_b: &Pubkey,
+    partner: &Pubkey,
+    partner_ata_a: &Pubkey,
+    partner_ata_b: &Pubkey,
+    a_to_b: bool,
+    amount: u64,
+    amount_limit: u64,
+    sqrt_price_limit: u128,
+    by_amount_in: bool,
+    payer: Pubkey,
+) -> Instruction {
     let mut accounts = vec![
-        AccountMeta::new_readonly(*clmm_config, false),
+        AccountMeta::new_readonly(clmmpool_info.clmm_config, false),
         AccountMeta::new(*clmmpool, false),
-        AccountMeta::new_readonly(*token_a, false),
-        AccountMeta::new_readonly(*token_b, false),
+        AccountMeta::new_readonly(clmmpool_info.token_a, false),
+        AccountMeta::new_readonly(clmmpool_info.token_b, false),
         AccountMeta::new(*account_a, false),
         AccountMeta::new(*account_b, false),
-        AccountMeta::new(*token_a_vault, false),
-        AccountMeta::new(*token_b_vault, false),
-        AccountMeta::new(*tick_array_map, false),
+        AccountMeta::new(clmmpool_info.token_a_vault, false),
+        AccountMeta::new(clmmpool_info.token_b_vault, false),
+        AccountMeta::new(TickArrayMap::find_address(clmmpool, program_id), false),
         AccountMeta::new(payer, true),
         AccountMeta::new_readonly(*partner, false),
         AccountMeta::new(*partner_ata_a, false),
         AccountMeta::new(*partner_ata_b, false),
         AccountMeta::new_readonly(spl_token::id(), false),
     ];
-    accounts.extend(remaining_accounts);
+    // The tick arrays the swap walks through go in as remaining accounts.
+    accounts.extend(swap_tick_array_accounts(
+        program_id,
+        clmmpool,
+        clmmpool_info,
+        array_map,
+        a_to_b,
+    ));
     Instruction {
-        program_id: SWAP_PROGRAM_ID,
+        program_id: *program_id,
         accounts,
-        data: distor,
+        data: instruction_data(
+            "swap_with_partner",
+            &SwapWithPartnerArgs {
+                a_to_b,
+                by_amount_in,
+                amount,
+                amount_limit,
+                sqrt_price_limit,
+            },
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::tick_array::TickArray;
+    use crate::utils::sighash;
+
+    #[test]
+    fn test_swap_with_partner() {
+        let clmmpool = Pubkey::new_unique();
+        let clmmpool_info = Clmmpool {
+            clmm_config: Pubkey::new_unique(),
+            token_a: Pubkey::new_unique(),
+            token_b: Pubkey::new_unique(),
+            token_a_vault: Pubkey::new_unique(),
+            token_b_vault: Pubkey::new_unique(),
+            tick_spacing: 60,
+            ..Default::default()
+        };
+        let mut array_map = TickArrayMap::default();
+        for array_index in [113usize, 115, 117] {
+            array_map.bitmap[array_index / 8] |= 1 << (array_index % 8);
+        }
+
+        let payer = Pubkey::new_unique();
+        let ix = swap_with_partner(
+            &SWAP_PROGRAM_ID,
+            &clmmpool,
+            &clmmpool_info,
+            &array_map,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            true,
+            1_000,
+            0,
+            0,
+            true,
+            payer,
+        );
+
+        assert_eq!(ix.program_id, SWAP_PROGRAM_ID);
+        assert_eq!(ix.accounts[0].pubkey, clmmpool_info.clmm_config);
+        assert_eq!(ix.accounts[6].pubkey, clmmpool_info.token_a_vault);
+        assert!(ix.accounts[9].is_signer);
+        let tick_arrays: Vec<Pubkey> = ix.accounts[14..].iter().map(|a| a.pubkey).collect();
+        assert_eq!(
+            tick_arrays,
+            [115, 113]
+                .iter()
+                .map(|i| TickArray::find_address(&clmmpool, *i, &SWAP_PROGRAM_ID))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            &ix.data[..8],
+            &sighash::sighash("global", "swap_with_partner")
+        );
     }
 }
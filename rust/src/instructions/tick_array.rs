@@ -0,0 +1,214 @@
+use rust_decimal::Decimal;
+use solana_program::instruction::Instruction;
+use solana_program::rent::Rent;
+use solana_sdk::pubkey::Pubkey;
+
+use super::pool::create_tick_array;
+use crate::error::ErrorCode;
+use crate::math::sqrt_price::SqrtPrice;
+use crate::math::tick_math::get_tick_at_sqrt_price;
+use crate::state::tick::Tick;
+use crate::state::tick_array::TickArray;
+use crate::state::tick_array_map::TickArrayMap;
+use crate::state::DISCRIMINATOR_LEN;
+
+/// The tick arrays a position range needs that were never created.
+#[derive(Debug, Clone, Default)]
+pub struct TickArraysPlan {
+    pub array_indexes: Vec<u16>,
+    pub tick_arrays: Vec<Pubkey>,
+    /// The lamports to make the missing tick arrays rent exempt.
+    pub rent: u64,
+    pub instructions: Vec<Instruction>,
+}
+
+/// The lamports to make a tick array rent exempt.
+pub fn tick_array_rent() -> u64 {
+    Rent::default().minimum_balance(DISCRIMINATOR_LEN + TickArray::LEN)
+}
+
+/// Plans the `createTickArray` instructions of the arrays covering `tick_lower_index` and
+/// `tick_upper_index` that are not in the pool's [TickArrayMap].
+pub fn plan_tick_arrays(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    clmmpool: &Pubkey,
+    tick_spacing: u16,
+    array_map: &TickArrayMap,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+) -> Result<TickArraysPlan, ErrorCode> {
+    if tick_spacing == 0 {
+        return Err(ErrorCode::InvalidTickSpacing);
+    }
+    for tick_index in [tick_lower_index, tick_upper_index] {
+        if tick_index < Tick::min(tick_spacing)
+            || tick_index > Tick::max(tick_spacing)
+            || tick_index % tick_spacing as i32 != 0
+        {
+            return Err(ErrorCode::InvalidTickIndex);
+        }
+    }
+    if tick_lower_index >= tick_upper_index {
+        return Err(ErrorCode::InvalidTickIndex);
+    }
+
+    let mut array_indexes = vec![
+        TickArray::array_index(tick_lower_index, tick_spacing),
+        TickArray::array_index(tick_upper_index, tick_spacing),
+    ];
+    array_indexes.dedup();
+    array_indexes.retain(|array_index| !array_map.is_seted(*array_index as usize));
+
+    let tick_arrays = array_indexes
+        .iter()
+        .map(|array_index| TickArray::find_address(clmmpool, *array_index, program_id))
+        .collect();
+    let instructions = array_indexes
+        .iter()
+        .map(|array_index| create_tick_array(program_id, payer, clmmpool, *array_index))
+        .collect();
+    Ok(TickArraysPlan {
+        rent: tick_array_rent() * array_indexes.len() as u64,
+        array_indexes,
+        tick_arrays,
+        instructions,
+    })
+}
+
+/// Plans the tick arrays of the ui price range of token a in token b, the prices are widened to
+/// the nearest usable ticks. Prices out of the sqrt price range fail with
+/// [ErrorCode::SqrtPriceOutOfBounds].
+#[allow(clippy::too_many_arguments)]
+pub fn plan_tick_arrays_for_price_range(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    clmmpool: &Pubkey,
+    tick_spacing: u16,
+    array_map: &TickArrayMap,
+    price_lower: Decimal,
+    price_upper: Decimal,
+    decimals_a: u8,
+    decimals_b: u8,
+) -> Result<TickArraysPlan, ErrorCode> {
+    if tick_spacing == 0 {
+        return Err(ErrorCode::InvalidTickSpacing);
+    }
+    if price_lower <= Decimal::ZERO || price_lower >= price_upper {
+        return Err(ErrorCode::InvalidTickIndex);
+    }
+    let price_to_tick = |price: Decimal| {
+        SqrtPrice::try_from_price(price, decimals_a, decimals_b).map(get_tick_at_sqrt_price)
+    };
+    let spacing = tick_spacing as i32;
+    let tick_lower_index =
+        (price_to_tick(price_lower)?.div_euclid(spacing) * spacing).max(Tick::min(tick_spacing));
+    let upper = price_to_tick(price_upper)?;
+    let tick_upper_index =
+        ((upper + spacing - 1).div_euclid(spacing) * spacing).min(Tick::max(tick_spacing));
+
+    plan_tick_arrays(
+        program_id,
+        payer,
+        clmmpool,
+        tick_spacing,
+        array_map,
+        tick_lower_index,
+        tick_upper_index,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::swap_with_partner::SWAP_PROGRAM_ID;
+
+    #[test]
+    fn test_plan_tick_arrays() {
+        let payer = Pubkey::new_unique();
+        let clmmpool = Pubkey::new_unique();
+        let mut array_map = TickArrayMap::default();
+        let lower_array = TickArray::array_index(-6000, 60) as usize;
+        array_map.bitmap[lower_array / 8] |= 1 << (lower_array % 8);
+
+        let plan = plan_tick_arrays(
+            &SWAP_PROGRAM_ID,
+            &payer,
+            &clmmpool,
+            60,
+            &array_map,
+            -6000,
+            6000,
+        )
+        .unwrap();
+        let upper_array = TickArray::array_index(6000, 60);
+        assert_eq!(plan.array_indexes, vec![upper_array]);
+        assert_eq!(
+            plan.tick_arrays,
+            vec![TickArray::find_address(
+                &clmmpool,
+                upper_array,
+                &SWAP_PROGRAM_ID
+            )]
+        );
+        assert_eq!(plan.rent, tick_array_rent());
+        assert_eq!(plan.instructions.len(), 1);
+
+        // Both ticks in one array already created.
+        let plan = plan_tick_arrays(
+            &SWAP_PROGRAM_ID,
+            &payer,
+            &clmmpool,
+            60,
+            &array_map,
+            -6000,
+            -5940,
+        )
+        .unwrap();
+        assert!(plan.instructions.is_empty());
+        assert_eq!(plan.rent, 0);
+
+        assert!(matches!(
+            plan_tick_arrays(
+                &SWAP_PROGRAM_ID,
+                &payer,
+                &clmmpool,
+                60,
+                &array_map,
+                -6000,
+                6001
+            ),
+            Err(ErrorCode::InvalidTickIndex)
+        ));
+
+        let plan = plan_tick_arrays_for_price_range(
+            &SWAP_PROGRAM_ID,
+            &payer,
+            &clmmpool,
+            60,
+            &TickArrayMap::default(),
+            Decimal::new(5, 1),
+            Decimal::new(2, 0),
+            6,
+            6,
+        )
+        .unwrap();
+        assert_eq!(plan.array_indexes.len(), 2);
+        assert_eq!(plan.rent, 2 * tick_array_rent());
+
+        assert!(matches!(
+            plan_tick_arrays_for_price_range(
+                &SWAP_PROGRAM_ID,
+                &payer,
+                &clmmpool,
+                60,
+                &array_map,
+                Decimal::new(1, 0),
+                Decimal::MAX,
+                6,
+                6,
+            ),
+            Err(ErrorCode::SqrtPriceOutOfBounds)
+        ));
+    }
+}
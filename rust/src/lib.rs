@@ -1,4 +1,5 @@
 pub mod amm;
+pub mod amm_interface;
 pub mod config;
 pub mod crema;
 pub mod error;
@@ -6,5 +7,6 @@ pub mod harness;
 pub mod instructions;
 pub mod math;
 pub mod pair;
+pub mod route;
 pub mod state;
 pub mod utils;
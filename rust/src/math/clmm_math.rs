@@ -374,8 +374,7 @@ pub fn compute_swap_step(
                 FEE_RATE_DENOMINATOR,
             );
             let max_amount_in =
-                get_delta_up_from_input(current_sqrt_price, target_sqrt_price, liquidity, a_to_b)
-                    .unwrap();
+                get_delta_up_from_input(current_sqrt_price, target_sqrt_price, liquidity, a_to_b)?;
             if max_amount_in > U256::from(amount_remain) {
                 amount_in = amount_remain;
                 fee_amount = amount.checked_sub(amount_remain).unwrap();
@@ -386,15 +385,17 @@ pub fn compute_swap_step(
                     a_to_b,
                 )?;
             } else {
-                amount_in = max_amount_in.as_u64();
+                amount_in = max_amount_in
+                    .checked_as_u64()
+                    .ok_or(ErrorCode::SwapAmountInOverflow)?;
                 fee_amount =
                     amount_in.mul_div_ceil(fee_rate as u64, FEE_RATE_DENOMINATOR - fee_rate as u64);
                 next_sqrt_price = target_sqrt_price;
             }
             amount_out =
-                get_delta_down_from_output(current_sqrt_price, next_sqrt_price, liquidity, a_to_b)
-                    .unwrap()
-                    .as_u64();
+                get_delta_down_from_output(current_sqrt_price, next_sqrt_price, liquidity, a_to_b)?
+                    .checked_as_u64()
+                    .ok_or(ErrorCode::SwapAmountOutOverflow)?;
         }
         false => {
             let max_amount_out = get_delta_down_from_output(
@@ -402,20 +403,21 @@ pub fn compute_swap_step(
                 target_sqrt_price,
                 liquidity,
                 a_to_b,
-            )
-            .unwrap();
+            )?;
             if max_amount_out > U256::from(amount) {
                 amount_out = amount;
                 next_sqrt_price =
                     get_next_sqrt_price_from_output(current_sqrt_price, liquidity, amount, a_to_b)?;
             } else {
-                amount_out = max_amount_out.as_u64();
+                amount_out = max_amount_out
+                    .checked_as_u64()
+                    .ok_or(ErrorCode::SwapAmountOutOverflow)?;
                 next_sqrt_price = target_sqrt_price;
             }
             amount_in =
-                get_delta_up_from_input(current_sqrt_price, next_sqrt_price, liquidity, a_to_b)
-                    .unwrap()
-                    .as_u64();
+                get_delta_up_from_input(current_sqrt_price, next_sqrt_price, liquidity, a_to_b)?
+                    .checked_as_u64()
+                    .ok_or(ErrorCode::SwapAmountInOverflow)?;
             fee_amount =
                 amount_in.mul_div_ceil(fee_rate as u64, FEE_RATE_DENOMINATOR - fee_rate as u64);
         }
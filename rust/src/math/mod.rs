@@ -2,5 +2,6 @@ pub mod bn;
 pub mod clmm_math;
 pub mod fee;
 pub mod full_math;
+pub mod rewarder;
 pub mod sqrt_price;
 pub mod tick_math;
@@ -0,0 +1,182 @@
+use crate::error::ErrorCode;
+use crate::math::full_math::FullMath;
+use crate::state::clmmpool::{Clmmpool, REWARDER_NUM};
+use crate::state::position::{growth_amount, Position};
+use crate::state::tick::Tick;
+use crate::state::tick_array::TickArray;
+
+/// The `growth_global` of every rewarder advanced to the unix `timestamp`.
+///
+/// Nothing is emitted while the pool has no liquidity, and a `timestamp` before the last update
+/// leaves the growths unchanged.
+pub fn next_reward_growths_global(clmmpool: &Clmmpool, timestamp: u64) -> [u128; REWARDER_NUM] {
+    let mut growths_global = [0u128; REWARDER_NUM];
+    for (growth_global, rewarder) in growths_global
+        .iter_mut()
+        .zip(clmmpool.rewarder_infos.0.iter())
+    {
+        *growth_global = rewarder.growth_global;
+    }
+    if clmmpool.liquidity == 0 || timestamp <= clmmpool.rewarder_last_updated_time {
+        return growths_global;
+    }
+
+    let time_delta = (timestamp - clmmpool.rewarder_last_updated_time) as u128;
+    for (growth_global, rewarder) in growths_global
+        .iter_mut()
+        .zip(clmmpool.rewarder_infos.0.iter())
+    {
+        if !rewarder.is_initialized() {
+            continue;
+        }
+        let growth_delta =
+            time_delta.mul_div_floor(rewarder.emissions_per_second, clmmpool.liquidity);
+        *growth_global = growth_global.wrapping_add(growth_delta);
+    }
+    growths_global
+}
+
+/// The reward growth inside the tick range of every rewarder, given the rewarders
+/// `growths_global`.
+pub fn get_reward_in_tick_range(
+    clmmpool: &Clmmpool,
+    growths_global: &[u128; REWARDER_NUM],
+    tick_lower: Option<&Tick>,
+    tick_upper: Option<&Tick>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+) -> [u128; REWARDER_NUM] {
+    let mut growths_inside = [0u128; REWARDER_NUM];
+    for (i, growth_inside) in growths_inside.iter_mut().enumerate() {
+        if !clmmpool.rewarder_infos.0[i].is_initialized() {
+            continue;
+        }
+        let growth_global = growths_global[i];
+        let growth_below = match tick_lower {
+            None => growth_global,
+            Some(tick_lower) => match clmmpool.current_tick_index < tick_lower_index {
+                true => growth_global.wrapping_sub(tick_lower.reward_growth_outside[i]),
+                false => tick_lower.reward_growth_outside[i],
+            },
+        };
+        let growth_above = match tick_upper {
+            None => 0,
+            Some(tick_upper) => match clmmpool.current_tick_index < tick_upper_index {
+                true => tick_upper.reward_growth_outside[i],
+                false => growth_global.wrapping_sub(tick_upper.reward_growth_outside[i]),
+            },
+        };
+        *growth_inside = growth_global
+            .wrapping_sub(growth_below)
+            .wrapping_sub(growth_above);
+    }
+    growths_inside
+}
+
+/// The reward of every rewarder `collectRewarder` would pay at the unix `timestamp`. The tick
+/// arrays hold the lower and the upper tick of the position.
+pub fn pending_rewards(
+    position: &Position,
+    clmmpool: &Clmmpool,
+    tick_lower_array: &TickArray,
+    tick_upper_array: &TickArray,
+    timestamp: u64,
+) -> Result<[u64; REWARDER_NUM], ErrorCode> {
+    let (tick_lower, tick_upper) = position.get_ticks(tick_lower_array, tick_upper_array)?;
+    let growths_inside = get_reward_in_tick_range(
+        clmmpool,
+        &next_reward_growths_global(clmmpool, timestamp),
+        tick_lower,
+        tick_upper,
+        position.tick_lower_index,
+        position.tick_upper_index,
+    );
+
+    let mut rewards = [0u64; REWARDER_NUM];
+    for (i, reward) in rewards.iter_mut().enumerate() {
+        let rewarder_info = &position.rewarder_infos[i];
+        let earned = growth_amount(
+            position.liquidity,
+            growths_inside[i].wrapping_sub(rewarder_info.growth_inside),
+        )?;
+        *reward = rewarder_info
+            .amount_owed
+            .checked_add(earned)
+            .ok_or(ErrorCode::OwedAmountOverflow)?;
+    }
+    Ok(rewards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::clmmpool::{Rewarder, Rewarders};
+    use crate::state::position::PositionReward;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_pending_rewards() {
+        let clmmpool = Pubkey::new_unique();
+        let rewarder = Rewarder {
+            mint: Pubkey::new_unique(),
+            // 2 tokens per second
+            emissions_per_second: 2 << 64,
+            growth_global: 1 << 64,
+            ..Default::default()
+        };
+        let mut pool = Clmmpool {
+            liquidity: 1 << 10,
+            current_tick_index: 0,
+            rewarder_infos: Rewarders([rewarder, Rewarder::default(), rewarder]),
+            rewarder_last_updated_time: 100,
+            ..Default::default()
+        };
+        let growths_global = next_reward_growths_global(&pool, 612);
+        // 512 seconds of 2 tokens over 1024 liquidity.
+        assert_eq!(growths_global, [2 << 64, 0, 2 << 64]);
+        assert_eq!(next_reward_growths_global(&pool, 50), [1 << 64, 0, 1 << 64]);
+
+        let mut tick_array = TickArray {
+            array_index: TickArray::array_index(0, 60),
+            tick_spacing: 60,
+            clmmpool,
+            ..Default::default()
+        };
+        let offset = tick_array.tick_offset(-60);
+        tick_array.ticks[offset].is_initialized = true;
+        tick_array.ticks[offset].reward_growth_outside = [1 << 63, 0, 1 << 63];
+        let offset = tick_array.tick_offset(60);
+        tick_array.ticks[offset].is_initialized = true;
+
+        let position = Position {
+            clmmpool,
+            liquidity: 1 << 10,
+            tick_lower_index: -60,
+            tick_upper_index: 60,
+            rewarder_infos: [
+                PositionReward {
+                    growth_inside: 1 << 63,
+                    amount_owed: 3,
+                },
+                PositionReward::default(),
+                PositionReward {
+                    growth_inside: 1 << 64,
+                    amount_owed: 0,
+                },
+            ],
+            ..Default::default()
+        };
+        // Reward growth inside is 2 - 0.5 - 0 = 1.5 per unit of liquidity.
+        assert_eq!(
+            pending_rewards(&position, &pool, &tick_array, &tick_array, 612).unwrap(),
+            [3 + 1024, 0, 512]
+        );
+
+        // No reward is emitted without liquidity.
+        pool.liquidity = 0;
+        assert_eq!(
+            next_reward_growths_global(&pool, 612),
+            [1 << 64, 0, 1 << 64]
+        );
+    }
+}
@@ -3,6 +3,9 @@ use std::ops::{Add, Div, Mul};
 
 use rust_decimal::prelude::*;
 
+use crate::error::ErrorCode;
+use crate::math::tick_math::{MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64};
+
 pub struct SqrtPrice {
     pub fixed_point: u128,
 }
@@ -28,6 +31,29 @@ impl SqrtPrice {
         }
     }
 
+    /// The Q64.64 sqrt price of the ui `price` like [SqrtPrice::from], fails instead of panicking
+    /// when the price is not positive or out of the sqrt price range.
+    pub fn try_from_price(
+        price: Decimal,
+        base_decimal: u8,
+        quote_decimal: u8,
+    ) -> Result<u128, ErrorCode> {
+        if price <= Decimal::ZERO {
+            return Err(ErrorCode::SqrtPriceOutOfBounds);
+        }
+        let exp = quote_decimal as i32 - base_decimal as i32;
+        let sqrt_price = Decimal::from_f64(10f64.powi(exp))
+            .and_then(|multi| multi.checked_mul(price))
+            .and_then(|price| price.sqrt())
+            .and_then(|sqrt_price| sqrt_price.checked_mul(Decimal::from(u64::MAX)))
+            .and_then(|sqrt_price| sqrt_price.to_u128())
+            .ok_or(ErrorCode::SqrtPriceOutOfBounds)?;
+        if !(MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64).contains(&sqrt_price) {
+            return Err(ErrorCode::SqrtPriceOutOfBounds);
+        }
+        Ok(sqrt_price)
+    }
+
     #[allow(dead_code)]
     pub fn to_decimal(&self) -> Decimal {
         let integer = Decimal::from_i128_with_scale((self.fixed_point >> 64) as i128, 0);
@@ -0,0 +1,80 @@
+use rust_decimal::Decimal;
+
+use super::fetcher::PoolInfo;
+use super::simulate_swap::{compute_swap_to_sqrt_price, ComputeSwapResult};
+use crate::error::ErrorCode;
+use crate::math::sqrt_price::SqrtPrice;
+
+/// The swap moving the pool price to a reference price.
+#[derive(Debug)]
+pub struct ArbitrageResult {
+    /// Sells token a, the pool price is above the reference price.
+    pub a2b: bool,
+    /// The sqrt price of the reference price.
+    pub target_sqrt_price: u128,
+    /// The amount in includes the fee.
+    pub swap_result: ComputeSwapResult,
+    /// The amount out minus the amount in valued at the reference price, in the output token.
+    pub profit: Decimal,
+}
+
+/// Sizes the swap that moves the pool to `price`, the ui price of token a in token b quoted
+/// elsewhere. Prices out of the sqrt price range fail with [ErrorCode::SqrtPriceOutOfBounds].
+pub fn compute_arbitrage(
+    pool_info: &PoolInfo,
+    price: Decimal,
+    decimals_a: u8,
+    decimals_b: u8,
+) -> Result<ArbitrageResult, ErrorCode> {
+    let target_sqrt_price = SqrtPrice::try_from_price(price, decimals_a, decimals_b)?;
+    let a2b = target_sqrt_price < pool_info.pool.current_sqrt_price;
+    let swap_result = compute_swap_to_sqrt_price(pool_info.clone(), target_sqrt_price)?;
+
+    // The price of token a in token b in token amounts.
+    let raw_price = SqrtPrice::new(target_sqrt_price).ui_price(0, 0);
+    let amount_in = Decimal::from(swap_result.amount_in);
+    let input_value = match a2b {
+        true => amount_in * raw_price,
+        false if swap_result.amount_in == 0 => Decimal::ZERO,
+        false => amount_in / raw_price,
+    };
+    Ok(ArbitrageResult {
+        a2b,
+        target_sqrt_price,
+        profit: Decimal::from(swap_result.amount_out) - input_value,
+        swap_result,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::test_utils::pool_info;
+
+    #[test]
+    fn test_compute_arbitrage() {
+        // A 1:1 pool of two 6 decimals tokens.
+        let pool_info = pool_info(&[(-6000, 6000)]);
+
+        // Token a is cheaper elsewhere, buy it in the pool with token b until it reaches 1.02.
+        let result = compute_arbitrage(&pool_info, Decimal::new(102, 2), 6, 6).unwrap();
+        assert!(!result.a2b);
+        assert_eq!(result.swap_result.next_sqrt_price, result.target_sqrt_price);
+        assert!(result.profit > Decimal::ZERO);
+
+        let result = compute_arbitrage(&pool_info, Decimal::new(98, 2), 6, 6).unwrap();
+        assert!(result.a2b);
+        assert!(result.profit > Decimal::ZERO);
+
+        // Within the fee the trade loses.
+        let result = compute_arbitrage(&pool_info, Decimal::new(1001, 3), 6, 6).unwrap();
+        assert!(result.profit < Decimal::ZERO);
+
+        for price in [Decimal::ZERO, Decimal::MAX] {
+            assert!(matches!(
+                compute_arbitrage(&pool_info, price, 6, 6),
+                Err(ErrorCode::SqrtPriceOutOfBounds)
+            ));
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use rust_decimal::prelude::*;
+
+use super::fetcher::PoolInfo;
+use super::simulate_swap::{compute_swap_to_sqrt_price, ComputeSwapResult};
+use crate::error::ErrorCode;
+use crate::math::full_math::FullMath;
+use crate::math::tick_math::{MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64};
+
+/// The swaps moving the pool price down and up by `price_change`.
+#[derive(Debug)]
+pub struct MarketDepth {
+    /// The relative price change, 0.01 for 1%.
+    pub price_change: Decimal,
+    /// Selling token a, moves the price down by `price_change`.
+    pub sell_a: ComputeSwapResult,
+    /// Selling token b, moves the price up by `price_change`.
+    pub sell_b: ComputeSwapResult,
+}
+
+/// The amounts to sell to move the pool price down and up by `price_change`, fee included.
+///
+/// The target prices are clamped to the price range of the pool, `price_change` has to be
+/// between 0 and 1 exclusive.
+pub fn market_depth(pool_info: &PoolInfo, price_change: Decimal) -> Result<MarketDepth, ErrorCode> {
+    if price_change <= Decimal::ZERO || price_change >= Decimal::ONE {
+        return Err(ErrorCode::InvalidPriceChange);
+    }
+    let current_sqrt_price = pool_info.pool.current_sqrt_price;
+    let down = scale_sqrt_price(current_sqrt_price, Decimal::ONE - price_change)?;
+    let up = scale_sqrt_price(current_sqrt_price, Decimal::ONE + price_change)?;
+    Ok(MarketDepth {
+        price_change,
+        sell_a: compute_swap_to_sqrt_price(pool_info.clone(), down)?,
+        sell_b: compute_swap_to_sqrt_price(pool_info.clone(), up)?,
+    })
+}
+
+/// The sqrt price of the price multiplied by `factor`.
+fn scale_sqrt_price(sqrt_price: u128, factor: Decimal) -> Result<u128, ErrorCode> {
+    let sqrt_factor = factor
+        .sqrt()
+        .and_then(|f| f.checked_mul(Decimal::from(1u128 << 64)))
+        .and_then(|f| f.to_u128())
+        .ok_or(ErrorCode::NumberCastError)?;
+    Ok(sqrt_price
+        .mul_shift_right(sqrt_factor, 64)
+        .clamp(MIN_SQRT_PRICE_X64, MAX_SQRT_PRICE_X64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::sqrt_price::SqrtPrice;
+    use crate::pair::test_utils::pool_info;
+
+    #[test]
+    fn test_market_depth() {
+        let pool_info = pool_info(&[(-600, 600)]);
+
+        let one = market_depth(&pool_info, Decimal::new(1, 2)).unwrap();
+        let price =
+            |result: &ComputeSwapResult| SqrtPrice::new(result.next_sqrt_price).ui_price(0, 0);
+        assert!((price(&one.sell_a) - Decimal::new(99, 2)).abs() < Decimal::new(1, 9));
+        assert!((price(&one.sell_b) - Decimal::new(101, 2)).abs() < Decimal::new(1, 9));
+        assert!(one.sell_a.amount_in > one.sell_a.amount_out);
+
+        // Past the position range, about 6% away, the liquidity runs out and the depth stops
+        // growing.
+        let five = market_depth(&pool_info, Decimal::new(5, 2)).unwrap();
+        let ten = market_depth(&pool_info, Decimal::new(10, 2)).unwrap();
+        let twenty = market_depth(&pool_info, Decimal::new(20, 2)).unwrap();
+        assert!(five.sell_a.amount_in > one.sell_a.amount_in);
+        assert!(ten.sell_b.amount_in > five.sell_b.amount_in);
+        assert_eq!(twenty.sell_b.amount_in, ten.sell_b.amount_in);
+    }
+
+    #[test]
+    fn test_market_depth_rejects_invalid_price_change() {
+        let pool_info = pool_info(&[(-600, 600)]);
+        for price_change in [
+            Decimal::ZERO,
+            Decimal::new(-1, 2),
+            Decimal::ONE,
+            Decimal::TWO,
+        ] {
+            assert!(matches!(
+                market_depth(&pool_info, price_change),
+                Err(ErrorCode::InvalidPriceChange)
+            ));
+        }
+    }
+}
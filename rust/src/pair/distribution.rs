@@ -0,0 +1,145 @@
+use rust_decimal::Decimal;
+
+use super::fetcher::PoolInfo;
+use crate::error::ErrorCode;
+use crate::math::clmm_math::{get_delta_a, get_delta_b};
+use crate::math::sqrt_price::SqrtPrice;
+use crate::math::tick_math::{get_sqrt_price_at_tick, MAX_TICK, MIN_TICK};
+
+/// The active liquidity between two adjacent initialized ticks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LiquidityBucket {
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    pub sqrt_price_lower: u128,
+    pub sqrt_price_upper: u128,
+    pub liquidity: u128,
+    /// The token a held by the bucket liquidity, at the current price of the pool.
+    pub amount_a: u64,
+    /// The token b held by the bucket liquidity, at the current price of the pool.
+    pub amount_b: u64,
+}
+
+impl LiquidityBucket {
+    /// The ui price range of token a in token b.
+    pub fn price_range(&self, decimals_a: u8, decimals_b: u8) -> (Decimal, Decimal) {
+        (
+            SqrtPrice::new(self.sqrt_price_lower).ui_price(decimals_a, decimals_b),
+            SqrtPrice::new(self.sqrt_price_upper).ui_price(decimals_a, decimals_b),
+        )
+    }
+}
+
+/// Splits the whole tick range at the initialized ticks of the pool and reconstructs the active
+/// liquidity of every bucket from the pool liquidity and the `liquidity_net` of the ticks.
+///
+/// Buckets without liquidity are kept, they are the gaps of the distribution.
+pub fn liquidity_distribution(pool_info: &PoolInfo) -> Result<Vec<LiquidityBucket>, ErrorCode> {
+    let pool = &pool_info.pool;
+    let mut ticks: Vec<(i32, i128)> = pool_info
+        .ticks
+        .iter()
+        .filter(|tick| tick.is_initialized && tick.index > MIN_TICK && tick.index < MAX_TICK)
+        .map(|tick| (tick.index, tick.liquidity_net))
+        .collect();
+    ticks.sort_by_key(|(index, _)| *index);
+    ticks.dedup_by_key(|(index, _)| *index);
+
+    let mut bounds = vec![(MIN_TICK, 0)];
+    bounds.extend(ticks);
+    bounds.push((MAX_TICK, 0));
+
+    // The bucket of the current tick holds the pool liquidity, crossing a tick upward adds its
+    // liquidity net.
+    let current = bounds
+        .iter()
+        .rposition(|(index, _)| *index <= pool.current_tick_index)
+        .unwrap_or(0)
+        .min(bounds.len() - 2);
+    let mut liquidities = vec![0u128; bounds.len() - 1];
+    liquidities[current] = pool.liquidity;
+    for i in current + 1..liquidities.len() {
+        liquidities[i] = add_liquidity_net(liquidities[i - 1], bounds[i].1)?;
+    }
+    for i in (0..current).rev() {
+        liquidities[i] = add_liquidity_net(liquidities[i + 1], -bounds[i + 1].1)?;
+    }
+
+    let mut buckets = Vec::with_capacity(liquidities.len());
+    for (i, liquidity) in liquidities.into_iter().enumerate() {
+        let (tick_lower_index, tick_upper_index) = (bounds[i].0, bounds[i + 1].0);
+        let sqrt_price_lower = get_sqrt_price_at_tick(tick_lower_index);
+        let sqrt_price_upper = get_sqrt_price_at_tick(tick_upper_index);
+        let current_sqrt_price = pool
+            .current_sqrt_price
+            .clamp(sqrt_price_lower, sqrt_price_upper);
+        let (amount_a, amount_b) = match liquidity {
+            0 => (0, 0),
+            _ => (
+                get_delta_a(current_sqrt_price, sqrt_price_upper, liquidity, false)?,
+                get_delta_b(sqrt_price_lower, current_sqrt_price, liquidity, false)?,
+            ),
+        };
+        buckets.push(LiquidityBucket {
+            tick_lower_index,
+            tick_upper_index,
+            sqrt_price_lower,
+            sqrt_price_upper,
+            liquidity,
+            amount_a,
+            amount_b,
+        });
+    }
+    Ok(buckets)
+}
+
+fn add_liquidity_net(liquidity: u128, liquidity_net: i128) -> Result<u128, ErrorCode> {
+    if liquidity_net >= 0 {
+        liquidity
+            .checked_add(liquidity_net as u128)
+            .ok_or(ErrorCode::LiquidityOverflow)
+    } else {
+        liquidity
+            .checked_sub(liquidity_net.unsigned_abs())
+            .ok_or(ErrorCode::LiquidityUnderflow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::test_utils::{pool_info, LIQUIDITY};
+
+    #[test]
+    fn test_liquidity_distribution() {
+        // Two positions, [-600, 600] and [1200, 6000], the second one above the current price.
+        let mut pool_info = pool_info(&[(-600, 600), (1200, 6000)]);
+        // the fetched ticks are not necessarily ordered
+        pool_info.ticks.reverse();
+        let buckets = liquidity_distribution(&pool_info).unwrap();
+        let ranges: Vec<(i32, i32, u128)> = buckets
+            .iter()
+            .map(|b| (b.tick_lower_index, b.tick_upper_index, b.liquidity))
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                (MIN_TICK, -600, 0),
+                (-600, 600, LIQUIDITY),
+                (600, 1200, 0),
+                (1200, 6000, LIQUIDITY),
+                (6000, MAX_TICK, 0),
+            ]
+        );
+
+        // The current bucket holds both tokens, the one above only token a.
+        let current = buckets[1];
+        assert!(current.amount_a > 0 && current.amount_a.abs_diff(current.amount_b) <= 1);
+        assert!(buckets[3].amount_a > 0);
+        assert_eq!(buckets[3].amount_b, 0);
+        assert_eq!((buckets[0].amount_a, buckets[0].amount_b), (0, 0));
+
+        let (price_lower, price_upper) = current.price_range(6, 6);
+        assert!(price_lower < Decimal::ONE && price_upper > Decimal::ONE);
+    }
+}
@@ -82,6 +82,10 @@ pub struct PoolInfo {
     pub tick_array_map: TickArrayMap,
     pub tick_arrays: Vec<TickArrayInfo>,
     pub ticks: Vec<TickInfo>,
+    /// The protocol fee rate of the pool's clmm config.
+    pub protocol_fee_rate: u16,
+    /// The fee rate of the partner the swap goes through, zero without partner.
+    pub partner_fee_rate: u16,
 }
 
 impl PoolInfo {
@@ -107,6 +111,14 @@ impl PoolInfo {
         ticks
     }
 
+    /// The tick array indexes, in swap order, a swap instruction carries for this pool.
+    pub fn swap_tick_array_indexes(&self, a2b: bool, count: usize) -> Vec<u16> {
+        let array_index =
+            TickArray::array_index(self.pool.current_tick_index, self.pool.tick_spacing);
+        self.tick_array_map
+            .swap_array_indexes(array_index, a2b, count)
+    }
+
     pub fn ticks_for_swap(&self, a2b: bool, limit: usize) -> (Vec<Pubkey>, Vec<Tick>) {
         let mut ticks = vec![];
         let mut array_addreses = vec![];
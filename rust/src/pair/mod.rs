@@ -1,2 +1,67 @@
+pub mod arbitrage;
+pub mod depth;
+pub mod distribution;
 pub mod fetcher;
 pub mod simulate_swap;
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use super::fetcher::{PoolInfo, TickInfo};
+    use crate::math::tick_math::get_sqrt_price_at_tick;
+    use crate::state::{clmmpool::Clmmpool, tick_array::TickArray, tick_array_map::TickArrayMap};
+
+    pub const LIQUIDITY: u128 = 1_000_000_000_000;
+
+    pub fn tick(index: i32, liquidity_net: i128) -> TickInfo {
+        TickInfo {
+            is_initialized: true,
+            index,
+            sqrt_price: get_sqrt_price_at_tick(index),
+            liquidity_net,
+            liquidity_gross: liquidity_net.unsigned_abs(),
+            fee_growth_outside_a: 0,
+            fee_growth_outside_b: 0,
+            reward_growth_outside: [0; 3],
+        }
+    }
+
+    /// A pool at tick 0 with tick spacing 60 and fee rate 3000, every position of `ranges` holds
+    /// [LIQUIDITY]. The tick array map lists the arrays of the ticks.
+    pub fn pool_info(ranges: &[(i32, i32)]) -> PoolInfo {
+        let mut pool = Clmmpool {
+            tick_spacing: 60,
+            fee_rate: 3000,
+            current_sqrt_price: get_sqrt_price_at_tick(0),
+            current_tick_index: 0,
+            ..Default::default()
+        };
+        let mut ticks: Vec<TickInfo> = vec![];
+        for (tick_lower_index, tick_upper_index) in ranges {
+            if *tick_lower_index <= 0 && *tick_upper_index > 0 {
+                pool.liquidity += LIQUIDITY;
+            }
+            for (index, liquidity_net) in [
+                (*tick_lower_index, LIQUIDITY as i128),
+                (*tick_upper_index, -(LIQUIDITY as i128)),
+            ] {
+                match ticks.iter_mut().find(|t| t.index == index) {
+                    Some(t) => t.liquidity_net += liquidity_net,
+                    None => ticks.push(tick(index, liquidity_net)),
+                }
+            }
+        }
+        ticks.sort_by_key(|t| t.index);
+
+        let mut tick_array_map = TickArrayMap::default();
+        for t in ticks.iter() {
+            let array_index = TickArray::array_index(t.index, pool.tick_spacing) as usize;
+            tick_array_map.bitmap[array_index / 8] |= 1 << (array_index % 8);
+        }
+        PoolInfo {
+            pool,
+            tick_array_map,
+            ticks,
+            ..Default::default()
+        }
+    }
+}
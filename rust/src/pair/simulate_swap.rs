@@ -1,8 +1,19 @@
 use super::fetcher::PoolInfo;
+use crate::error::ErrorCode;
 use crate::math::{
-    clmm_math::{compute_swap_step, SwapStepResult},
-    tick_math::get_tick_at_sqrt_price,
+    bn::Downcast,
+    clmm_math::{
+        compute_swap_step, get_delta_down_from_output, get_delta_up_from_input, SwapStepResult,
+        FEE_RATE_DENOMINATOR,
+    },
+    full_math::FullMath,
+    tick_math::{
+        get_sqrt_price_at_tick, get_tick_at_sqrt_price, MAX_SQRT_PRICE_X64, MAX_TICK,
+        MIN_SQRT_PRICE_X64, MIN_TICK,
+    },
 };
+use crate::state::tick_array::TickArray;
+use solana_sdk::pubkey::Pubkey;
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct StepInfo {
@@ -43,39 +54,306 @@ impl StepInfo {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CrossedTick {
+    pub tick_index: i32,
+    pub sqrt_price: u128,
+    /// The pool liquidity after the tick is crossed.
+    pub liquidity_after: u128,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SwapTrace {
+    pub steps: Vec<StepInfo>,
+    pub crossed_ticks: Vec<CrossedTick>,
+    /// Indexes of the tick arrays holding the start tick and every crossed tick, in swap order.
+    pub tick_array_indexes: Vec<u16>,
+}
+
+impl SwapTrace {
+    fn touch_tick_array(&mut self, tick_index: i32, tick_spacing: u16) {
+        let array_index = TickArray::array_index(tick_index, tick_spacing);
+        if !self.tick_array_indexes.contains(&array_index) {
+            self.tick_array_indexes.push(array_index);
+        }
+    }
+
+    pub fn tick_array_addresses(&self, clmmpool: &Pubkey, program_id: &Pubkey) -> Vec<Pubkey> {
+        self.tick_array_indexes
+            .iter()
+            .map(|array_index| TickArray::find_address(clmmpool, *array_index, program_id))
+            .collect()
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct ComputeSwapResult {
     pub amount_in: u64,
     pub amount_out: u64,
     pub fee_amount: u64,
     pub next_sqrt_price: u128,
+    /// The part of `fee_amount` left to liquidity providers.
+    pub lp_fee_amount: u64,
+    /// The part of `fee_amount` owed to protocol.
+    pub protocol_fee_amount: u64,
+    /// The part of `fee_amount` paid to partner, taken from the protocol fee.
+    pub partner_fee_amount: u64,
+    /// The swap stopped at `sqrt_price_limit` before the amount was used up.
+    pub is_price_limited: bool,
+    /// The swap ran past the last supplied tick array before the amount was used up.
+    pub is_tick_arrays_exceeded: bool,
 }
 
 impl ComputeSwapResult {
-    fn update(&mut self, step_result: &SwapStepResult) {
-        self.amount_in = self.amount_in.checked_add(step_result.amount_in).unwrap();
-        self.amount_out = self.amount_out.checked_add(step_result.amount_out).unwrap();
-        self.fee_amount = self.fee_amount.checked_add(step_result.fee_amount).unwrap();
+    fn update(&mut self, step_result: &SwapStepResult) -> Result<(), ErrorCode> {
+        self.amount_in = self
+            .amount_in
+            .checked_add(step_result.amount_in)
+            .ok_or(ErrorCode::SwapAmountInOverflow)?;
+        self.amount_out = self
+            .amount_out
+            .checked_add(step_result.amount_out)
+            .ok_or(ErrorCode::SwapAmountOutOverflow)?;
+        self.fee_amount = self
+            .fee_amount
+            .checked_add(step_result.fee_amount)
+            .ok_or(ErrorCode::SwapFeeAmountOverflow)?;
         self.next_sqrt_price = step_result.next_sqrt_price;
+        Ok(())
     }
 }
 
+/// The sqrt price limit that lets a swap walk the whole price range in the given direction.
+pub fn default_sqrt_price_limit(a2b: bool) -> u128 {
+    if a2b {
+        MIN_SQRT_PRICE_X64
+    } else {
+        MAX_SQRT_PRICE_X64
+    }
+}
+
+/// Checks `sqrt_price_limit` the same way the program does before a swap.
+pub fn check_sqrt_price_limit(
+    current_sqrt_price: u128,
+    sqrt_price_limit: u128,
+    a2b: bool,
+) -> Result<(), ErrorCode> {
+    let is_valid = if a2b {
+        sqrt_price_limit >= MIN_SQRT_PRICE_X64 && sqrt_price_limit < current_sqrt_price
+    } else {
+        sqrt_price_limit <= MAX_SQRT_PRICE_X64 && sqrt_price_limit > current_sqrt_price
+    };
+    if !is_valid {
+        return Err(ErrorCode::WrongSqrtPriceLimit);
+    }
+    Ok(())
+}
+
+/// Simulates a swap over the whole price range.
 pub fn compute_swap(
     pool_info: PoolInfo,
     a2b: bool,
     by_amount_in: bool,
     amount: u64,
-) -> ComputeSwapResult {
+) -> Result<ComputeSwapResult, ErrorCode> {
+    compute_swap_with_price_limit(
+        pool_info,
+        a2b,
+        by_amount_in,
+        amount,
+        default_sqrt_price_limit(a2b),
+    )
+}
+
+/// Simulates a swap that stops once the pool price reaches `sqrt_price_limit`,
+/// the remainder of `amount` is left unfilled.
+pub fn compute_swap_with_price_limit(
+    pool_info: PoolInfo,
+    a2b: bool,
+    by_amount_in: bool,
+    amount: u64,
+    sqrt_price_limit: u128,
+) -> Result<ComputeSwapResult, ErrorCode> {
+    let (swap_result, _) =
+        compute_swap_with_trace(pool_info, a2b, by_amount_in, amount, sqrt_price_limit)?;
+    Ok(swap_result)
+}
+
+/// Same as [compute_swap_with_price_limit], also returns every step and tick crossing of the walk.
+pub fn compute_swap_with_trace(
+    pool_info: PoolInfo,
+    a2b: bool,
+    by_amount_in: bool,
+    amount: u64,
+    sqrt_price_limit: u128,
+) -> Result<(ComputeSwapResult, SwapTrace), ErrorCode> {
+    simulate_swap(pool_info, a2b, by_amount_in, amount, sqrt_price_limit, None)
+}
+
+/// Simulates a swap over only the first `tick_array_count` tick arrays picked from the tick
+/// array map, the same arrays the swap instruction carries. Returns the picked array indexes.
+pub fn compute_swap_in_tick_arrays(
+    pool_info: PoolInfo,
+    a2b: bool,
+    by_amount_in: bool,
+    amount: u64,
+    sqrt_price_limit: u128,
+    tick_array_count: usize,
+) -> Result<(ComputeSwapResult, Vec<u16>), ErrorCode> {
+    let array_indexes = pool_info.swap_tick_array_indexes(a2b, tick_array_count);
+    let boundary = tick_arrays_boundary(
+        &array_indexes,
+        pool_info.pool.tick_spacing,
+        pool_info.pool.current_sqrt_price,
+        a2b,
+    );
+    let (swap_result, _) = simulate_swap(
+        pool_info,
+        a2b,
+        by_amount_in,
+        amount,
+        sqrt_price_limit,
+        Some(boundary),
+    )?;
+    Ok((swap_result, array_indexes))
+}
+
+/// The furthest sqrt price a swap can reach without another tick array.
+fn tick_arrays_boundary(
+    array_indexes: &[u16],
+    tick_spacing: u16,
+    current_sqrt_price: u128,
+    a2b: bool,
+) -> u128 {
+    match array_indexes.last() {
+        None => current_sqrt_price,
+        Some(last) => {
+            if a2b {
+                let tick_index = TickArray::array_start_tick_index(*last, tick_spacing);
+                get_sqrt_price_at_tick(tick_index.max(MIN_TICK))
+            } else {
+                let tick_index = TickArray::array_start_tick_index(*last + 1, tick_spacing);
+                get_sqrt_price_at_tick(tick_index.min(MAX_TICK))
+            }
+        }
+    }
+}
+
+/// Simulates the swap that moves the pool price exactly to `target_sqrt_price`, the direction
+/// is a to b when the target is below the current price. The amount in includes the fee.
+pub fn compute_swap_to_sqrt_price(
+    pool_info: PoolInfo,
+    target_sqrt_price: u128,
+) -> Result<ComputeSwapResult, ErrorCode> {
+    if !(MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64).contains(&target_sqrt_price) {
+        return Err(ErrorCode::SqrtPriceOutOfBounds);
+    }
+    let mut pool = pool_info.pool;
+    let a2b = target_sqrt_price < pool.current_sqrt_price;
+    let (_, ticks) = pool_info.ticks_for_swap(a2b, 100);
+    let mut swap_result = ComputeSwapResult {
+        next_sqrt_price: pool.current_sqrt_price,
+        ..Default::default()
+    };
+
+    let mut next_ticks = ticks.into_iter();
+    while pool.current_sqrt_price != target_sqrt_price {
+        let mut next_tick = next_ticks.next();
+        let step_sqrt_price = match next_tick {
+            Some(tick) if a2b => tick.sqrt_price.max(target_sqrt_price),
+            Some(tick) => tick.sqrt_price.min(target_sqrt_price),
+            None => target_sqrt_price,
+        };
+
+        let amount_in = get_delta_up_from_input(
+            pool.current_sqrt_price,
+            step_sqrt_price,
+            pool.liquidity,
+            a2b,
+        )?
+        .checked_as_u64()
+        .ok_or(ErrorCode::SwapAmountInOverflow)?;
+        let amount_out = get_delta_down_from_output(
+            pool.current_sqrt_price,
+            step_sqrt_price,
+            pool.liquidity,
+            a2b,
+        )?
+        .checked_as_u64()
+        .ok_or(ErrorCode::SwapAmountOutOverflow)?;
+        // The same fee `compute_swap_step` charges on an input that reaches the step target.
+        let fee_amount = amount_in.mul_div_ceil(
+            pool.fee_rate as u64,
+            FEE_RATE_DENOMINATOR - pool.fee_rate as u64,
+        );
+        swap_result.update(&SwapStepResult {
+            next_sqrt_price: step_sqrt_price,
+            amount_in,
+            amount_out,
+            fee_amount,
+        })?;
+        let (partner_fee, protocol_fee) = pool.update_fee(
+            fee_amount,
+            pool_info.partner_fee_rate,
+            pool_info.protocol_fee_rate,
+            a2b,
+        );
+        swap_result.partner_fee_amount += partner_fee;
+        swap_result.protocol_fee_amount += protocol_fee;
+        swap_result.lp_fee_amount += fee_amount - partner_fee - protocol_fee;
+
+        pool.current_sqrt_price = step_sqrt_price;
+        match next_tick.as_mut() {
+            Some(tick) if tick.is_initialized && tick.sqrt_price == step_sqrt_price => {
+                pool.current_tick_index = if a2b { tick.index - 1 } else { tick.index };
+                pool.liquidity = tick.cross_update(&pool, a2b);
+            }
+            _ => pool.current_tick_index = get_tick_at_sqrt_price(step_sqrt_price),
+        }
+    }
+
+    swap_result.amount_in = swap_result
+        .amount_in
+        .checked_add(swap_result.fee_amount)
+        .ok_or(ErrorCode::SwapAmountInOverflow)?;
+    Ok(swap_result)
+}
+
+fn simulate_swap(
+    pool_info: PoolInfo,
+    a2b: bool,
+    by_amount_in: bool,
+    amount: u64,
+    sqrt_price_limit: u128,
+    tick_arrays_boundary: Option<u128>,
+) -> Result<(ComputeSwapResult, SwapTrace), ErrorCode> {
     let (_, ticks) = pool_info.ticks_for_swap(a2b, 100);
     let mut pool = pool_info.pool;
     let mut remainer_amount = amount;
     let mut swap_result = ComputeSwapResult::default();
     let mut next_idx: usize = 0;
-    let mut steps = vec![];
+    let mut trace = SwapTrace::default();
+
+    if sqrt_price_limit != default_sqrt_price_limit(a2b) {
+        check_sqrt_price_limit(pool.current_sqrt_price, sqrt_price_limit, a2b)?;
+    }
+    // The tighter of the price limit and the tick arrays boundary is where the walk stops.
+    let stop_sqrt_price = match tick_arrays_boundary {
+        Some(boundary) if a2b => sqrt_price_limit.max(boundary),
+        Some(boundary) => sqrt_price_limit.min(boundary),
+        None => sqrt_price_limit,
+    };
+    // The walk starts in the tick array of the current tick in both directions, the first array
+    // [PoolInfo::swap_tick_array_indexes] picks.
+    trace.touch_tick_array(pool.current_tick_index, pool.tick_spacing);
 
     while remainer_amount > 0 && next_idx < ticks.len() {
         let mut next_tick = ticks[next_idx];
-        let target_sqrt_price = next_tick.sqrt_price;
+        let target_sqrt_price = if a2b {
+            next_tick.sqrt_price.max(stop_sqrt_price)
+        } else {
+            next_tick.sqrt_price.min(stop_sqrt_price)
+        };
 
         let step_result = compute_swap_step(
             pool.current_sqrt_price,
@@ -84,8 +362,7 @@ pub fn compute_swap(
             remainer_amount,
             pool.fee_rate,
             by_amount_in,
-        )
-        .unwrap();
+        )?;
 
         let mut step_info = StepInfo::from(
             remainer_amount,
@@ -97,15 +374,29 @@ pub fn compute_swap(
         if step_result.amount_in != 0 {
             match by_amount_in {
                 true => {
-                    remainer_amount = remainer_amount.checked_sub(step_result.amount_in).unwrap();
-                    remainer_amount = remainer_amount.checked_sub(step_result.fee_amount).unwrap();
+                    remainer_amount = remainer_amount
+                        .checked_sub(step_result.amount_in)
+                        .and_then(|v| v.checked_sub(step_result.fee_amount))
+                        .ok_or(ErrorCode::RemainerAmountUnderflow)?;
                 }
                 false => {
-                    remainer_amount = remainer_amount.checked_sub(step_result.amount_out).unwrap();
+                    remainer_amount = remainer_amount
+                        .checked_sub(step_result.amount_out)
+                        .ok_or(ErrorCode::RemainerAmountUnderflow)?;
                 }
             }
             // update swap result
-            swap_result.update(&step_result);
+            swap_result.update(&step_result)?;
+
+            let (partner_fee, protocol_fee) = pool.update_fee(
+                step_result.fee_amount,
+                pool_info.partner_fee_rate,
+                pool_info.protocol_fee_rate,
+                a2b,
+            );
+            swap_result.partner_fee_amount += partner_fee;
+            swap_result.protocol_fee_amount += protocol_fee;
+            swap_result.lp_fee_amount += step_result.fee_amount - partner_fee - protocol_fee;
         }
         step_info.after_remainer = remainer_amount;
         step_info.amount_used = amount - remainer_amount;
@@ -119,16 +410,197 @@ pub fn compute_swap(
                 next_tick.index
             };
             pool.liquidity = next_tick.cross_update(&pool, a2b);
+            trace.crossed_ticks.push(CrossedTick {
+                tick_index: next_tick.index,
+                sqrt_price: next_tick.sqrt_price,
+                liquidity_after: pool.liquidity,
+            });
+            trace.touch_tick_array(next_tick.index, pool.tick_spacing);
         } else {
             pool.current_sqrt_price = step_result.next_sqrt_price;
             pool.current_tick_index = get_tick_at_sqrt_price(pool.current_sqrt_price);
         }
         step_info.after_tick_index = pool.current_tick_index;
-        steps.push(step_info);
+        trace.steps.push(step_info);
 
+        if pool.current_sqrt_price == stop_sqrt_price {
+            if stop_sqrt_price == sqrt_price_limit {
+                swap_result.is_price_limited = remainer_amount > 0;
+            } else {
+                swap_result.is_tick_arrays_exceeded = remainer_amount > 0;
+            }
+            break;
+        }
         next_idx += 1;
     }
 
-    swap_result.amount_in += swap_result.fee_amount;
-    swap_result
+    swap_result.next_sqrt_price = pool.current_sqrt_price;
+    swap_result.amount_in = swap_result
+        .amount_in
+        .checked_add(swap_result.fee_amount)
+        .ok_or(ErrorCode::SwapAmountInOverflow)?;
+    Ok((swap_result, trace))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compute_swap, compute_swap_in_tick_arrays, compute_swap_to_sqrt_price,
+        compute_swap_with_price_limit, compute_swap_with_trace, default_sqrt_price_limit,
+    };
+    use crate::error::ErrorCode;
+    use crate::math::tick_math::get_sqrt_price_at_tick;
+    use crate::pair::fetcher::PoolInfo;
+    use crate::pair::test_utils::{self, LIQUIDITY};
+    use crate::state::tick_array::TickArray;
+
+    // Two positions around tick 0, the ticks live in tick arrays 113, 115 and 117.
+    fn pool_info() -> PoolInfo {
+        test_utils::pool_info(&[(-6000, 6000), (-600, 600)])
+    }
+
+    #[test]
+    fn test_compute_swap_exact_out() {
+        let exact_in = compute_swap(pool_info(), true, true, 1_000_000).unwrap();
+        assert_eq!(exact_in.amount_in, 1_000_000);
+
+        let exact_out = compute_swap(pool_info(), true, false, exact_in.amount_out).unwrap();
+        assert_eq!(exact_out.amount_out, exact_in.amount_out);
+        assert!(exact_out.amount_in <= exact_in.amount_in);
+        assert!(exact_out.fee_amount > 0);
+    }
+
+    #[test]
+    fn test_compute_swap_with_price_limit() {
+        let limit = get_sqrt_price_at_tick(-100);
+        let result =
+            compute_swap_with_price_limit(pool_info(), true, true, 1_000_000_000_000, limit)
+                .unwrap();
+        assert!(result.is_price_limited);
+        assert_eq!(result.next_sqrt_price, limit);
+        assert!(result.amount_in < 1_000_000_000_000);
+
+        let unlimited = compute_swap(pool_info(), true, true, result.amount_in).unwrap();
+        // rounding of the fee can differ by one unit between the two walks
+        assert!(unlimited.amount_out.abs_diff(result.amount_out) <= 1);
+
+        let err = compute_swap_with_price_limit(
+            pool_info(),
+            true,
+            true,
+            1_000_000,
+            get_sqrt_price_at_tick(100),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ErrorCode::WrongSqrtPriceLimit));
+    }
+
+    #[test]
+    fn test_compute_swap_with_trace() {
+        let (result, trace) = compute_swap_with_trace(
+            pool_info(),
+            false,
+            true,
+            u64::MAX / 2,
+            default_sqrt_price_limit(false),
+        )
+        .unwrap();
+        let steps_in: u64 = trace.steps.iter().map(|s| s.amount_in + s.fee_amount).sum();
+        assert_eq!(steps_in, result.amount_in);
+        assert_eq!(trace.crossed_ticks.len(), 2);
+        assert_eq!(trace.crossed_ticks[0].tick_index, 600);
+        assert_eq!(trace.crossed_ticks[0].liquidity_after, LIQUIDITY);
+        assert_eq!(trace.crossed_ticks[1].tick_index, 6000);
+        assert_eq!(trace.crossed_ticks[1].liquidity_after, 0);
+        assert_eq!(trace.tick_array_indexes, vec![115, 117]);
+    }
+
+    #[test]
+    fn test_compute_swap_in_tick_arrays() {
+        let amount = 1_000_000_000_000;
+        let limit = default_sqrt_price_limit(true);
+
+        let (result, array_indexes) =
+            compute_swap_in_tick_arrays(pool_info(), true, true, amount, limit, 1).unwrap();
+        assert_eq!(array_indexes, vec![115]);
+        assert!(result.is_tick_arrays_exceeded);
+        assert_eq!(
+            result.next_sqrt_price,
+            get_sqrt_price_at_tick(TickArray::array_start_tick_index(115, 60))
+        );
+
+        let (result, array_indexes) =
+            compute_swap_in_tick_arrays(pool_info(), true, true, amount, limit, 3).unwrap();
+        assert_eq!(array_indexes, vec![115, 113]);
+        assert!(!result.is_tick_arrays_exceeded);
+        let unbounded = compute_swap(pool_info(), true, true, amount).unwrap();
+        assert_eq!(result.amount_out, unbounded.amount_out);
+
+        let (result, _) =
+            compute_swap_in_tick_arrays(pool_info(), true, true, amount, limit, 0).unwrap();
+        assert!(result.is_tick_arrays_exceeded);
+        assert_eq!(result.amount_out, 0);
+    }
+
+    #[test]
+    fn test_compute_swap_fee_split() {
+        let mut pool_info = pool_info();
+        pool_info.protocol_fee_rate = 2000;
+        pool_info.partner_fee_rate = 5000;
+
+        let result = compute_swap(pool_info, false, true, 10_000_000_000).unwrap();
+        assert_eq!(
+            result.lp_fee_amount + result.protocol_fee_amount + result.partner_fee_amount,
+            result.fee_amount
+        );
+        // 20% of the fee goes to protocol and half of that to partner
+        assert!(result.protocol_fee_amount.abs_diff(result.fee_amount / 10) <= 2);
+        assert!(result.partner_fee_amount.abs_diff(result.fee_amount / 10) <= 2);
+    }
+
+    #[test]
+    fn test_compute_swap_to_sqrt_price() {
+        // Down to tick -1200, crossing the tick at -600.
+        let target = get_sqrt_price_at_tick(-1200);
+        let result = compute_swap_to_sqrt_price(pool_info(), target).unwrap();
+        assert_eq!(result.next_sqrt_price, target);
+        assert!(result.fee_amount > 0);
+
+        let exact_in = compute_swap(pool_info(), true, true, result.amount_in).unwrap();
+        assert!(exact_in.next_sqrt_price.abs_diff(target) < 1 << 32);
+        assert!(exact_in.amount_out.abs_diff(result.amount_out) <= 2);
+
+        let result = compute_swap_to_sqrt_price(pool_info(), get_sqrt_price_at_tick(300)).unwrap();
+        let exact_out = compute_swap(pool_info(), false, false, result.amount_out).unwrap();
+        assert!(exact_out.amount_in.abs_diff(result.amount_in) <= 2);
+
+        let result = compute_swap_to_sqrt_price(pool_info(), get_sqrt_price_at_tick(0)).unwrap();
+        assert_eq!((result.amount_in, result.amount_out), (0, 0));
+        assert!(matches!(
+            compute_swap_to_sqrt_price(pool_info(), 0),
+            Err(ErrorCode::SqrtPriceOutOfBounds)
+        ));
+    }
+
+    #[test]
+    fn test_trace_starts_in_tick_array_of_current_tick() {
+        // The current tick is the last tick of array 115, a b2a swap starts in that array too.
+        let current_tick_index = TickArray::array_start_tick_index(116, 60) - 1;
+        let mut pool_info = pool_info();
+        pool_info.pool.current_tick_index = current_tick_index;
+        pool_info.pool.current_sqrt_price = get_sqrt_price_at_tick(current_tick_index);
+        pool_info.pool.liquidity = LIQUIDITY;
+
+        let array_indexes = pool_info.swap_tick_array_indexes(false, 3);
+        assert_eq!(array_indexes, vec![115, 117]);
+        let (_, trace) = compute_swap_with_trace(
+            pool_info,
+            false,
+            true,
+            1_000_000_000,
+            default_sqrt_price_limit(false),
+        )
+        .unwrap();
+        assert_eq!(trace.tick_array_indexes[0], array_indexes[0]);
+    }
 }
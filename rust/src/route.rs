@@ -0,0 +1,195 @@
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use jupiter::jupiter_override::{Route, Swap, SwapLeg};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use std::ops::Range;
+
+use crate::crema::CREMA_PROGRAMS;
+use crate::error::ErrorCode;
+use crate::state::tick_array_map::MAX_SWAP_TICK_ARRAYS;
+
+/// The accounts of the `route` instruction before the swap leg accounts.
+pub const ROUTE_ACCOUNTS_LEN: usize = 3;
+/// The accounts of a Crema swap leg before its tick arrays.
+pub const CREMA_LEG_ACCOUNTS_LEN: usize = 15;
+
+/// A Crema swap of a decoded Jupiter route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CremaLeg {
+    /// The position of the swap among all swaps of the route, in execution order.
+    pub swap_index: usize,
+    /// The accounts of the swap in the route instruction accounts, tick arrays included.
+    pub accounts: Range<usize>,
+    pub clmmpool: Pubkey,
+}
+
+pub struct DecodedRoute {
+    pub route: Route,
+    pub crema_legs: Vec<CremaLeg>,
+}
+
+/// Decodes the data of a Jupiter `route` instruction.
+pub fn decode_route(data: &[u8]) -> Result<Route, ErrorCode> {
+    if data.len() < 8 || data[..8] != Route::DISCRIMINATOR {
+        return Err(ErrorCode::InvalidRouteData);
+    }
+    Route::try_from_slice(&data[8..]).map_err(|_| ErrorCode::InvalidRouteData)
+}
+
+/// Decodes a Jupiter `route` instruction and locates the accounts of its Crema swaps.
+///
+/// A Crema swap starts at the Crema program account, its tick arrays are the writable accounts
+/// following the fixed accounts. The platform fee account is never part of a swap.
+pub fn decode_route_instruction(ix: &Instruction) -> Result<DecodedRoute, ErrorCode> {
+    if ix.program_id != jupiter::ID {
+        return Err(ErrorCode::InvalidRouteData);
+    }
+    let route = decode_route(&ix.data)?;
+
+    let mut swaps = vec![];
+    flatten_swaps(&route.swap_leg, &mut swaps);
+
+    let accounts_end = if route.platform_fee_bps > 0 {
+        ix.accounts.len().saturating_sub(1)
+    } else {
+        ix.accounts.len()
+    };
+    let mut cursor = ROUTE_ACCOUNTS_LEN;
+    let mut crema_legs = vec![];
+    for (swap_index, swap) in swaps.iter().enumerate() {
+        if !matches!(swap, Swap::Crema) {
+            continue;
+        }
+        let start = (cursor..accounts_end)
+            .find(|i| CREMA_PROGRAMS.contains_key(&ix.accounts[*i].pubkey))
+            .ok_or(ErrorCode::InvalidRouteData)?;
+        let mut end = start + CREMA_LEG_ACCOUNTS_LEN;
+        if end > accounts_end {
+            return Err(ErrorCode::InvalidRouteData);
+        }
+        while end < accounts_end
+            && end - start < CREMA_LEG_ACCOUNTS_LEN + MAX_SWAP_TICK_ARRAYS
+            && ix.accounts[end].is_writable
+        {
+            end += 1;
+        }
+        crema_legs.push(CremaLeg {
+            swap_index,
+            accounts: start..end,
+            clmmpool: ix.accounts[start + 2].pubkey,
+        });
+        cursor = end;
+    }
+
+    Ok(DecodedRoute { route, crema_legs })
+}
+
+/// Collects the swaps of the leg in execution order.
+fn flatten_swaps<'a>(swap_leg: &'a SwapLeg, swaps: &mut Vec<&'a Swap>) {
+    match swap_leg {
+        SwapLeg::Chain { swap_legs } => {
+            for swap_leg in swap_legs {
+                flatten_swaps(swap_leg, swaps);
+            }
+        }
+        SwapLeg::Split { split_legs } => {
+            for split_leg in split_legs {
+                flatten_swaps(&split_leg.swap_leg, swaps);
+            }
+        }
+        SwapLeg::Swap { swap } => swaps.push(swap),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::swap_with_partner::SWAP_PROGRAM_ID;
+    use anchor_lang::InstructionData;
+    use jupiter::jupiter_override::SplitLeg;
+    use solana_sdk::instruction::AccountMeta;
+
+    fn crema_leg_accounts(clmmpool: Pubkey, tick_arrays: usize) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(SWAP_PROGRAM_ID, false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new(clmmpool, false),
+        ];
+        accounts.extend(
+            (3..CREMA_LEG_ACCOUNTS_LEN)
+                .map(|_| AccountMeta::new_readonly(Pubkey::new_unique(), false)),
+        );
+        accounts.extend((0..tick_arrays).map(|_| AccountMeta::new(Pubkey::new_unique(), false)));
+        accounts
+    }
+
+    #[test]
+    fn test_route_round_trip() {
+        let route = Route {
+            swap_leg: SwapLeg::Chain {
+                swap_legs: vec![
+                    SwapLeg::Swap { swap: Swap::Crema },
+                    SwapLeg::Split {
+                        split_legs: vec![
+                            SplitLeg {
+                                percent: 40,
+                                swap_leg: SwapLeg::Swap {
+                                    swap: Swap::Whirlpool { a_to_b: true },
+                                },
+                            },
+                            SplitLeg {
+                                percent: 60,
+                                swap_leg: SwapLeg::Swap { swap: Swap::Crema },
+                            },
+                        ],
+                    },
+                ],
+            },
+            in_amount: 1_000,
+            quoted_out_amount: 990,
+            slippage_bps: 50,
+            platform_fee_bps: 1,
+        };
+        let data = route.data();
+        let decoded = decode_route(&data).unwrap();
+        assert_eq!(decoded.data(), data);
+        assert_eq!(decoded.in_amount, 1_000);
+        assert_eq!(decoded.quoted_out_amount, 990);
+        assert_eq!(decoded.slippage_bps, 50);
+        assert!(decode_route(&data[..data.len() - 1]).is_err());
+
+        let (pool_0, pool_1) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut accounts: Vec<AccountMeta> = (0..ROUTE_ACCOUNTS_LEN)
+            .map(|_| AccountMeta::new(Pubkey::new_unique(), false))
+            .collect();
+        accounts.extend(crema_leg_accounts(pool_0, 2));
+        // the whirlpool swap starts with its readonly program account
+        accounts.push(AccountMeta::new_readonly(Pubkey::new_unique(), false));
+        accounts.extend((0..10).map(|_| AccountMeta::new(Pubkey::new_unique(), false)));
+        let second_start = accounts.len();
+        accounts.extend(crema_leg_accounts(pool_1, 1));
+        // platform fee account
+        accounts.push(AccountMeta::new(Pubkey::new_unique(), false));
+
+        let ix = Instruction {
+            program_id: jupiter::ID,
+            accounts,
+            data,
+        };
+        let decoded = decode_route_instruction(&ix).unwrap();
+        assert_eq!(
+            decoded.crema_legs,
+            vec![
+                CremaLeg {
+                    swap_index: 0,
+                    accounts: 3..20,
+                    clmmpool: pool_0,
+                },
+                CremaLeg {
+                    swap_index: 2,
+                    accounts: second_start..second_start + 16,
+                    clmmpool: pool_1,
+                },
+            ]
+        );
+    }
+}
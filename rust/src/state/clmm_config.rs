@@ -0,0 +1,31 @@
+use super::AnchorAccount;
+use borsh::BorshDeserialize;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(BorshDeserialize, Default, Debug, Clone, Copy)]
+pub struct ClmmConfig {
+    /// The authority to manage the protocol config.
+    pub protocol_authority: Pubkey,
+    /// The authority to claim the protocol fee.
+    pub protocol_fee_claim_authority: Pubkey,
+    /// The numerator of protocol fee rate, the denominator is 1_000_0.
+    pub protocol_fee_rate: u16,
+    /// The new protocol authority waiting to accept the transfer.
+    pub pending_authority: Pubkey,
+    /// The authority to create clmmpool.
+    pub create_pool_authority: Pubkey,
+}
+
+impl ClmmConfig {
+    pub const LEN: usize = 32 + 32 + 2 + 32 + 32;
+
+    pub fn find_address(program_id: &Pubkey) -> Pubkey {
+        let (address, _) = Pubkey::find_program_address(&[b"clmmconfig"], program_id);
+        address
+    }
+}
+
+impl AnchorAccount for ClmmConfig {
+    const NAME: &'static str = "ClmmConfig";
+    const DATA_LEN: usize = ClmmConfig::LEN;
+}
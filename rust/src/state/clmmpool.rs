@@ -1,3 +1,5 @@
+use super::AnchorAccount;
+use crate::error::ErrorCode;
 use crate::math::full_math::FullMath;
 use borsh::BorshDeserialize;
 use solana_sdk::pubkey::Pubkey;
@@ -70,12 +72,62 @@ impl Clmmpool {
         + 8
         + 1;
 
+    /// The clmmpool address of the mint pair, the mints are ordered canonically first.
+    pub fn find_address(
+        clmm_config: &Pubkey,
+        token_a: &Pubkey,
+        token_b: &Pubkey,
+        tick_spacing: u16,
+        program_id: &Pubkey,
+    ) -> Pubkey {
+        let (token_a, token_b) = Clmmpool::order_mints(token_a, token_b);
+        let (address, _) = Pubkey::find_program_address(
+            &[
+                b"clmmpool",
+                clmm_config.as_ref(),
+                token_a.as_ref(),
+                token_b.as_ref(),
+                tick_spacing.to_le_bytes().as_ref(),
+            ],
+            program_id,
+        );
+        address
+    }
+
+    /// Orders the mints into the (token a, token b) of the clmmpool.
+    pub fn order_mints(mint_x: &Pubkey, mint_y: &Pubkey) -> (Pubkey, Pubkey) {
+        if mint_x < mint_y {
+            (*mint_x, *mint_y)
+        } else {
+            (*mint_y, *mint_x)
+        }
+    }
+
     pub fn get_tick_map_address(pool: &Pubkey, program_id: &Pubkey) -> Pubkey {
         let (expect_address, _) =
             Pubkey::find_program_address(&[b"tick_array_map", pool.as_ref()], program_id);
         expect_address
     }
 
+    /// The protocol fee of token a and token b owed to the protocol, collected by
+    /// `collect_protocol_fee`.
+    pub fn protocol_fee_owed(&self) -> (u64, u64) {
+        (self.fee_protocol_token_a, self.fee_protocol_token_b)
+    }
+
+    /// The initialized rewarder at `rewarder_index`.
+    pub fn get_rewarder(&self, rewarder_index: u8) -> Result<&Rewarder, ErrorCode> {
+        let rewarder = self
+            .rewarder_infos
+            .0
+            .get(rewarder_index as usize)
+            .ok_or(ErrorCode::InvalidRewarderIndex(rewarder_index))?;
+        if !rewarder.is_initialized() {
+            return Err(ErrorCode::RewarderNotInitialized(rewarder_index));
+        }
+        Ok(rewarder)
+    }
+
     pub fn update_fee(
         &mut self,
         fee_amount: u64,
@@ -122,6 +174,11 @@ impl Clmmpool {
     }
 }
 
+impl AnchorAccount for Clmmpool {
+    const NAME: &'static str = "Clmmpool";
+    const DATA_LEN: usize = Clmmpool::LEN;
+}
+
 #[derive(Copy, Clone, BorshDeserialize, Default, Debug, Eq, PartialEq)]
 pub struct Rewarder {
     pub mint_wrapper: Pubkey,
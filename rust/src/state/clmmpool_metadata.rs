@@ -0,0 +1,60 @@
+use super::AnchorAccount;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(BorshSerialize, BorshDeserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct ClmmpoolMetadata {
+    /// The [Clmmpool] address.
+    pub clmmpool: Pubkey,
+    /// The number of positions opened in the clmmpool.
+    pub position_nums: u64,
+    /// The name of position nft.
+    pub name: String,
+    /// The symbol of position nft.
+    pub symbol: String,
+    /// The metadata uri of position nft.
+    pub uri: String,
+}
+
+impl ClmmpoolMetadata {
+    /// The size without the string bytes.
+    pub const MIN_LEN: usize = 32 + 8 + 4 + 4 + 4;
+
+    pub fn find_address(clmmpool: &Pubkey, program_id: &Pubkey) -> Pubkey {
+        let (address, _) =
+            Pubkey::find_program_address(&[b"clmmpool_metadata", clmmpool.as_ref()], program_id);
+        address
+    }
+}
+
+impl AnchorAccount for ClmmpoolMetadata {
+    const NAME: &'static str = "ClmmpoolMetadata";
+    const DATA_LEN: usize = ClmmpoolMetadata::MIN_LEN;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClmmpoolMetadata;
+    use crate::state::{identify_account, AccountType, AnchorAccount};
+    use borsh::BorshSerialize;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_clmmpool_metadata_decode() {
+        let metadata = ClmmpoolMetadata {
+            clmmpool: Pubkey::new_unique(),
+            position_nums: 42,
+            name: "Crema Position".to_string(),
+            symbol: "CLP".to_string(),
+            uri: "https://example.com/position.json".to_string(),
+        };
+        let mut data = ClmmpoolMetadata::discriminator().to_vec();
+        data.extend(metadata.try_to_vec().unwrap());
+        // the account is allocated with room for longer strings
+        data.resize(data.len() + 64, 0);
+
+        let decoded = ClmmpoolMetadata::try_deserialize(&Pubkey::new_unique(), &data).unwrap();
+        assert_eq!(decoded, metadata);
+        assert_eq!(identify_account(&data), Some(AccountType::ClmmpoolMetadata));
+    }
+}
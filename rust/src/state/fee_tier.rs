@@ -0,0 +1,34 @@
+use super::AnchorAccount;
+use borsh::BorshDeserialize;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(BorshDeserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeTier {
+    /// The numerator of fee rate, the denominator is 1_000_000.
+    pub fee_rate: u16,
+    /// The tick spacing of the clmmpools created with this fee tier.
+    pub tick_spacing: u16,
+    /// The bump
+    pub bump: u8,
+}
+
+impl FeeTier {
+    pub const LEN: usize = 2 + 2 + 1;
+
+    pub fn find_address(clmm_config: &Pubkey, tick_spacing: u16, program_id: &Pubkey) -> Pubkey {
+        let (address, _) = Pubkey::find_program_address(
+            &[
+                b"fee_tier",
+                clmm_config.as_ref(),
+                tick_spacing.to_le_bytes().as_ref(),
+            ],
+            program_id,
+        );
+        address
+    }
+}
+
+impl AnchorAccount for FeeTier {
+    const NAME: &'static str = "FeeTier";
+    const DATA_LEN: usize = FeeTier::LEN;
+}
@@ -1,4 +1,157 @@
+pub mod clmm_config;
 pub mod clmmpool;
+pub mod clmmpool_metadata;
+pub mod fee_tier;
+pub mod partner;
+pub mod position;
 pub mod tick;
 pub mod tick_array;
 pub mod tick_array_map;
+
+use crate::error::ErrorCode;
+use crate::utils::sighash::sighash;
+use borsh::BorshDeserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use clmm_config::ClmmConfig;
+use clmmpool::Clmmpool;
+use clmmpool_metadata::ClmmpoolMetadata;
+use fee_tier::FeeTier;
+use partner::Partner;
+use position::Position;
+use tick_array::TickArray;
+use tick_array_map::TickArrayMap;
+
+/// The anchor account discriminator length.
+pub const DISCRIMINATOR_LEN: usize = 8;
+
+/// An account of the clmmpool program, stored as anchor discriminator followed by borsh data.
+pub trait AnchorAccount: BorshDeserialize {
+    /// The account name in the program idl.
+    const NAME: &'static str;
+    /// The least size of the account data without discriminator.
+    const DATA_LEN: usize;
+
+    /// The first 8 bytes of `sha256("account:<NAME>")`.
+    fn discriminator() -> [u8; 8] {
+        sighash("account", Self::NAME)
+    }
+
+    /// Decodes the account data at `address` after checking its size and discriminator.
+    fn try_deserialize(address: &Pubkey, data: &[u8]) -> Result<Self, ErrorCode> {
+        if data.len() < DISCRIMINATOR_LEN + Self::DATA_LEN {
+            return Err(ErrorCode::InvalidAccountSize {
+                address: *address,
+                expected: DISCRIMINATOR_LEN + Self::DATA_LEN,
+                actual: data.len(),
+            });
+        }
+        if data[..DISCRIMINATOR_LEN] != Self::discriminator() {
+            return Err(ErrorCode::InvalidAccountDiscriminator(*address));
+        }
+        Self::deserialize(&mut &data[DISCRIMINATOR_LEN..])
+            .map_err(|_| ErrorCode::AccountDeserializeFailed(*address))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountType {
+    ClmmConfig,
+    Clmmpool,
+    ClmmpoolMetadata,
+    FeeTier,
+    Partner,
+    Position,
+    TickArray,
+    TickArrayMap,
+}
+
+impl AccountType {
+    pub const ALL: [AccountType; 8] = [
+        AccountType::ClmmConfig,
+        AccountType::Clmmpool,
+        AccountType::ClmmpoolMetadata,
+        AccountType::FeeTier,
+        AccountType::Partner,
+        AccountType::Position,
+        AccountType::TickArray,
+        AccountType::TickArrayMap,
+    ];
+
+    pub fn discriminator(&self) -> [u8; 8] {
+        match self {
+            AccountType::ClmmConfig => ClmmConfig::discriminator(),
+            AccountType::Clmmpool => Clmmpool::discriminator(),
+            AccountType::ClmmpoolMetadata => ClmmpoolMetadata::discriminator(),
+            AccountType::FeeTier => FeeTier::discriminator(),
+            AccountType::Partner => Partner::discriminator(),
+            AccountType::Position => Position::discriminator(),
+            AccountType::TickArray => TickArray::discriminator(),
+            AccountType::TickArrayMap => TickArrayMap::discriminator(),
+        }
+    }
+}
+
+/// Identifies the account type from the discriminator of the account data.
+pub fn identify_account(data: &[u8]) -> Option<AccountType> {
+    if data.len() < DISCRIMINATOR_LEN {
+        return None;
+    }
+    AccountType::ALL
+        .into_iter()
+        .find(|account_type| data[..DISCRIMINATOR_LEN] == account_type.discriminator())
+}
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use super::{clmmpool::Clmmpool, AnchorAccount, DISCRIMINATOR_LEN};
+
+    /// Zeroed account data of `T` behind its discriminator.
+    pub fn zeroed_account_data<T: AnchorAccount>() -> Vec<u8> {
+        let mut data = T::discriminator().to_vec();
+        data.resize(DISCRIMINATOR_LEN + T::DATA_LEN, 0);
+        data
+    }
+
+    /// Zeroed clmmpool account data with a usable `tick_spacing`.
+    pub fn clmmpool_account_data(tick_spacing: u16) -> Vec<u8> {
+        let mut data = zeroed_account_data::<Clmmpool>();
+        // tick_spacing follows the five pubkeys
+        let offset = DISCRIMINATOR_LEN + 5 * 32;
+        data[offset..offset + 2].copy_from_slice(&tick_spacing.to_le_bytes());
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{identify_account, AccountType, AnchorAccount, DISCRIMINATOR_LEN};
+    use crate::error::ErrorCode;
+    use crate::state::{clmmpool::Clmmpool, tick_array_map::TickArrayMap};
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_try_deserialize() {
+        let address = Pubkey::new_unique();
+        let mut data = Clmmpool::discriminator().to_vec();
+        data.resize(DISCRIMINATOR_LEN + Clmmpool::LEN.max(TickArrayMap::LEN), 0);
+
+        assert!(Clmmpool::try_deserialize(&address, &data).is_ok());
+        assert_eq!(identify_account(&data), Some(AccountType::Clmmpool));
+        assert!(matches!(
+            TickArrayMap::try_deserialize(&address, &data),
+            Err(ErrorCode::InvalidAccountDiscriminator(_))
+        ));
+        assert!(matches!(
+            Clmmpool::try_deserialize(&address, &data[..100]),
+            Err(ErrorCode::InvalidAccountSize { .. })
+        ));
+
+        data[0] ^= 1;
+        assert!(matches!(
+            Clmmpool::try_deserialize(&address, &data),
+            Err(ErrorCode::InvalidAccountDiscriminator(_))
+        ));
+        assert_eq!(identify_account(&data), None);
+    }
+}
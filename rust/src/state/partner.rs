@@ -0,0 +1,48 @@
+use super::AnchorAccount;
+use borsh::BorshDeserialize;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(BorshDeserialize, Default, Debug, Clone)]
+pub struct Partner {
+    /// The authority to claim the partner fee.
+    pub partner_fee_claim_authority: Pubkey,
+    /// The new claim authority waiting to accept the transfer.
+    pub pending_authority: Pubkey,
+    /// The base key the partner address derived from.
+    pub base: Pubkey,
+    /// The numerator of partner fee rate, the denominator is 1_000_0.
+    /// The partner fee is taken from the protocol fee.
+    pub fee_rate: u16,
+    /// The bump
+    pub bump: u8,
+    /// The unix timestamp the partner fee starts.
+    pub start_time: u64,
+    /// The unix timestamp the partner fee ends.
+    pub end_time: u64,
+    /// The partner name.
+    pub name: String,
+}
+
+impl Partner {
+    /// The size without the name bytes.
+    pub const MIN_LEN: usize = 32 + 32 + 32 + 2 + 1 + 8 + 8 + 4;
+
+    pub fn find_address(base: &Pubkey, program_id: &Pubkey) -> Pubkey {
+        let (address, _) = Pubkey::find_program_address(&[b"partner", base.as_ref()], program_id);
+        address
+    }
+
+    /// The partner fee rate applied to a swap at `timestamp`, zero outside the partner period.
+    pub fn current_fee_rate(&self, timestamp: u64) -> u16 {
+        if timestamp >= self.start_time && timestamp < self.end_time {
+            self.fee_rate
+        } else {
+            0
+        }
+    }
+}
+
+impl AnchorAccount for Partner {
+    const NAME: &'static str = "Partner";
+    const DATA_LEN: usize = Partner::MIN_LEN;
+}
@@ -0,0 +1,254 @@
+use super::clmmpool::{Clmmpool, REWARDER_NUM};
+use super::{tick::Tick, tick_array::TickArray, AnchorAccount};
+use crate::error::ErrorCode;
+use crate::math::bn::{Downcast, Shift};
+use crate::math::full_math::FullMath;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(BorshSerialize, BorshDeserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PositionReward {
+    /// Q64.64 number that tracks the reward growth inside the position range at last update.
+    pub growth_inside: u128,
+    /// The amount of reward owed to the position.
+    pub amount_owed: u64,
+}
+
+impl PositionReward {
+    pub const LEN: usize = 16 + 8;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    /// The [Clmmpool] address.
+    pub clmmpool: Pubkey,
+    /// The position nft mint address.
+    pub position_nft_mint: Pubkey,
+
+    /// The liquidity of the position.
+    pub liquidity: u128,
+    /// The lower tick index of the position range.
+    pub tick_lower_index: i32,
+    /// The upper tick index of the position range.
+    pub tick_upper_index: i32,
+
+    /// The fee growth a inside the position range at last update, Q64.64.
+    pub fee_growth_inside_a: u128,
+    /// The amounts of token a owed to the position.
+    pub fee_owed_a: u64,
+    /// The fee growth b inside the position range at last update, Q64.64.
+    pub fee_growth_inside_b: u128,
+    /// The amounts of token b owed to the position.
+    pub fee_owed_b: u64,
+
+    pub rewarder_infos: [PositionReward; REWARDER_NUM],
+}
+
+impl Position {
+    pub const LEN: usize =
+        32 + 32 + 16 + 4 + 4 + 16 + 8 + 16 + 8 + REWARDER_NUM * PositionReward::LEN;
+
+    pub fn find_address(position_nft_mint: &Pubkey, program_id: &Pubkey) -> Pubkey {
+        let (address, _) =
+            Pubkey::find_program_address(&[b"position", position_nft_mint.as_ref()], program_id);
+        address
+    }
+
+    /// The lower and upper tick index of the position range.
+    pub fn tick_range(&self) -> (i32, i32) {
+        (self.tick_lower_index, self.tick_upper_index)
+    }
+
+    pub fn liquidity(&self) -> u128 {
+        self.liquidity
+    }
+
+    /// The owed fee of token a and token b recorded at last update.
+    pub fn fee_owed(&self) -> (u64, u64) {
+        (self.fee_owed_a, self.fee_owed_b)
+    }
+
+    /// The owed reward of every rewarder recorded at last update.
+    pub fn reward_owed(&self) -> [u64; REWARDER_NUM] {
+        let mut reward_owed = [0u64; REWARDER_NUM];
+        for (owed, rewarder) in reward_owed.iter_mut().zip(self.rewarder_infos.iter()) {
+            *owed = rewarder.amount_owed;
+        }
+        reward_owed
+    }
+
+    /// The fee of token a and token b `collectFee` would pay now, the owed fee plus the fee earned
+    /// since last update. The tick arrays hold the lower and the upper tick of the position.
+    pub fn pending_fee(
+        &self,
+        clmmpool: &Clmmpool,
+        tick_lower_array: &TickArray,
+        tick_upper_array: &TickArray,
+    ) -> Result<(u64, u64), ErrorCode> {
+        let (tick_lower, tick_upper) = self.get_ticks(tick_lower_array, tick_upper_array)?;
+        let (fee_growth_inside_a, fee_growth_inside_b) = Tick::get_fee_in_tick_range(
+            clmmpool,
+            tick_lower,
+            tick_upper,
+            self.tick_lower_index,
+            self.tick_upper_index,
+        );
+        let fee_a = growth_amount(
+            self.liquidity,
+            fee_growth_inside_a.wrapping_sub(self.fee_growth_inside_a),
+        )?;
+        let fee_b = growth_amount(
+            self.liquidity,
+            fee_growth_inside_b.wrapping_sub(self.fee_growth_inside_b),
+        )?;
+        Ok((
+            self.fee_owed_a
+                .checked_add(fee_a)
+                .ok_or(ErrorCode::OwedAmountOverflow)?,
+            self.fee_owed_b
+                .checked_add(fee_b)
+                .ok_or(ErrorCode::OwedAmountOverflow)?,
+        ))
+    }
+
+    /// The lower and upper tick of the position in the tick arrays, `None` if not initialized.
+    pub(crate) fn get_ticks<'a>(
+        &self,
+        tick_lower_array: &'a TickArray,
+        tick_upper_array: &'a TickArray,
+    ) -> Result<(Option<&'a Tick>, Option<&'a Tick>), ErrorCode> {
+        if tick_lower_array.clmmpool != self.clmmpool || tick_upper_array.clmmpool != self.clmmpool
+        {
+            return Err(ErrorCode::PositionAndClmmpoolNotMatch);
+        }
+        if !tick_lower_array.is_in_array(self.tick_lower_index)
+            || !tick_upper_array.is_in_array(self.tick_upper_index)
+        {
+            return Err(ErrorCode::TickNotInArray);
+        }
+        Ok((
+            tick_lower_array.get_tick(self.tick_lower_index),
+            tick_upper_array.get_tick(self.tick_upper_index),
+        ))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.liquidity == 0
+            && self.fee_owed_a == 0
+            && self.fee_owed_b == 0
+            && self.rewarder_infos.iter().all(|r| r.amount_owed == 0)
+    }
+}
+
+/// The amount earned by `liquidity` over a Q64.64 `growth`.
+pub(crate) fn growth_amount(liquidity: u128, growth: u128) -> Result<u64, ErrorCode> {
+    liquidity
+        .full_mul(growth)
+        .shift_right(64)
+        .checked_as_u64()
+        .ok_or(ErrorCode::MultiplicationOverflow)
+}
+
+impl AnchorAccount for Position {
+    const NAME: &'static str = "Position";
+    const DATA_LEN: usize = Position::LEN;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Position, PositionReward};
+    use crate::state::clmmpool::Clmmpool;
+    use crate::state::tick_array::TickArray;
+    use crate::state::AnchorAccount;
+    use borsh::BorshSerialize;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_position_decode() {
+        let position = Position {
+            clmmpool: Pubkey::new_unique(),
+            position_nft_mint: Pubkey::new_unique(),
+            liquidity: 1_000_000,
+            tick_lower_index: -120,
+            tick_upper_index: 240,
+            fee_owed_a: 5,
+            fee_owed_b: 7,
+            rewarder_infos: [
+                PositionReward {
+                    growth_inside: 1,
+                    amount_owed: 11,
+                },
+                PositionReward::default(),
+                PositionReward {
+                    growth_inside: 3,
+                    amount_owed: 33,
+                },
+            ],
+            ..Default::default()
+        };
+        let mut data = Position::discriminator().to_vec();
+        data.extend(position.try_to_vec().unwrap());
+        assert_eq!(data.len(), 8 + Position::LEN);
+
+        let address = Position::find_address(&position.position_nft_mint, &Pubkey::new_unique());
+        let decoded = Position::try_deserialize(&address, &data).unwrap();
+        assert_eq!(decoded, position);
+        assert_eq!(decoded.tick_range(), (-120, 240));
+        assert_eq!(decoded.fee_owed(), (5, 7));
+        assert_eq!(decoded.reward_owed(), [11, 0, 33]);
+        assert!(!decoded.is_empty());
+    }
+
+    #[test]
+    fn test_pending_fee() {
+        let clmmpool = Pubkey::new_unique();
+        let position = Position {
+            clmmpool,
+            liquidity: 1 << 20,
+            tick_lower_index: -120,
+            tick_upper_index: 120,
+            fee_growth_inside_a: 1 << 64,
+            fee_owed_a: 5,
+            fee_growth_inside_b: u128::MAX,
+            fee_owed_b: 7,
+            ..Default::default()
+        };
+        let pool = Clmmpool {
+            current_tick_index: 0,
+            fee_growth_global_a: 10 << 64,
+            fee_growth_global_b: 4 << 64,
+            ..Default::default()
+        };
+        let mut tick_array = TickArray {
+            array_index: TickArray::array_index(0, 60),
+            tick_spacing: 60,
+            clmmpool,
+            ..Default::default()
+        };
+        for (tick_index, fee_growth_outside_a, fee_growth_outside_b) in
+            [(-120, 3 << 64, 1 << 64), (120, 2 << 64, 1 << 64)]
+        {
+            let offset = tick_array.tick_offset(tick_index);
+            let tick = &mut tick_array.ticks[offset];
+            tick.is_initialized = true;
+            tick.index = tick_index;
+            tick.fee_growth_outside_a = fee_growth_outside_a;
+            tick.fee_growth_outside_b = fee_growth_outside_b;
+        }
+
+        // Fee growth inside is 10 - 3 - 2 = 5 for a and 4 - 1 - 1 = 2 for b, the checkpoint of b
+        // wraps around.
+        assert_eq!(
+            position
+                .pending_fee(&pool, &tick_array, &tick_array)
+                .unwrap(),
+            (5 + (4 << 20), 7 + (2 << 20))
+        );
+
+        let mut other_array = tick_array;
+        other_array.array_index += 1;
+        assert!(position
+            .pending_fee(&pool, &other_array, &tick_array)
+            .is_err());
+    }
+}
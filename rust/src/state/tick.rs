@@ -39,7 +39,6 @@ impl Tick {
         MAX_TICK - MAX_TICK % tick_spacing as i32
     }
 
-    #[allow(dead_code)]
     pub fn get_fee_in_tick_range(
         clmmpool: &Clmmpool,
         tick_lower: Option<&Tick>,
@@ -1,4 +1,4 @@
-use super::tick::Tick;
+use super::{tick::Tick, AnchorAccount};
 use crate::math::tick_math::MIN_TICK;
 use borsh::BorshDeserialize;
 use solana_sdk::pubkey::Pubkey;
@@ -30,6 +30,11 @@ impl Default for TickArray {
     }
 }
 
+impl AnchorAccount for TickArray {
+    const NAME: &'static str = "TickArray";
+    const DATA_LEN: usize = TickArray::LEN;
+}
+
 impl TickArray {
     pub const CAP: usize = 64;
     pub const LEN: usize = 2 + 2 + 32 + Tick::LEN * TickArray::CAP;
@@ -54,6 +59,12 @@ impl TickArray {
         ((tick_index - min) / array_spacing) as u16
     }
 
+    /// The first tick index covered by the tick array at `array_index`.
+    #[inline]
+    pub fn array_start_tick_index(array_index: u16, tick_spacing: u16) -> i32 {
+        Tick::min(tick_spacing) + (array_index as usize * CAP * tick_spacing as usize) as i32
+    }
+
     #[inline]
     #[allow(dead_code)]
     pub fn array_spacing(&self) -> usize {
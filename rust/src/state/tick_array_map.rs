@@ -1,6 +1,10 @@
+use super::AnchorAccount;
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_sdk::pubkey::Pubkey;
 
+/// The max number of tick arrays a swap instruction carries as remaining accounts.
+pub const MAX_SWAP_TICK_ARRAYS: usize = 3;
+
 #[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
 pub struct TickArrayMap {
     pub bitmap: [u8; 868],
@@ -12,7 +16,13 @@ impl Default for TickArrayMap {
     }
 }
 
+impl AnchorAccount for TickArrayMap {
+    const NAME: &'static str = "TickArrayMap";
+    const DATA_LEN: usize = TickArrayMap::LEN;
+}
+
 impl TickArrayMap {
+    pub const LEN: usize = 868;
     pub const MAX_BIT_INDEX: usize = (868 * 8) - 1;
     pub const MIN_BIT_INDEX: usize = 0;
 
@@ -85,4 +95,22 @@ impl TickArrayMap {
             }
         }
     }
+
+    /// Picks the tick arrays a swap starting in `array_index` walks through, in swap order.
+    pub fn swap_array_indexes(&self, array_index: u16, a_to_b: bool, count: usize) -> Vec<u16> {
+        let mut array_indexes = vec![];
+        let mut next = if self.is_seted(array_index.into()) {
+            Some(array_index as usize)
+        } else {
+            self.next_seted(array_index.into(), a_to_b)
+        };
+        while let Some(index) = next {
+            if array_indexes.len() >= count {
+                break;
+            }
+            array_indexes.push(index as u16);
+            next = self.next_seted(index, a_to_b);
+        }
+        array_indexes
+    }
 }
@@ -1,2 +1,3 @@
 pub mod hash;
+pub mod pda;
 pub mod sighash;
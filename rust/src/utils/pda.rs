@@ -0,0 +1,43 @@
+use solana_sdk::{pubkey, pubkey::Pubkey};
+
+/// The metaplex token metadata program that owns the position nft metadata.
+pub const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// The associated token account of `wallet` for `mint`.
+pub fn get_associated_token_address(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+    let (address, _) = Pubkey::find_program_address(
+        &[wallet.as_ref(), spl_token::id().as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    );
+    address
+}
+
+/// The metadata account of the position nft.
+pub fn find_position_metadata_address(position_nft_mint: &Pubkey) -> Pubkey {
+    let (address, _) = Pubkey::find_program_address(
+        &[
+            b"metadata",
+            METADATA_PROGRAM_ID.as_ref(),
+            position_nft_mint.as_ref(),
+        ],
+        &METADATA_PROGRAM_ID,
+    );
+    address
+}
+
+/// The master edition account of the position nft.
+pub fn find_position_edition_address(position_nft_mint: &Pubkey) -> Pubkey {
+    let (address, _) = Pubkey::find_program_address(
+        &[
+            b"metadata",
+            METADATA_PROGRAM_ID.as_ref(),
+            position_nft_mint.as_ref(),
+            b"edition",
+        ],
+        &METADATA_PROGRAM_ID,
+    );
+    address
+}